@@ -0,0 +1,115 @@
+// The `:` command line: parsing what was typed into a Command for App to run
+
+use crate::database::DumpFormat;
+use crate::user_config::{HistoryFilter, HistoryFormat};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    // Dump the current/selected table; the format comes from the argument or the
+    // file extension, and the path defaults to <table>.sql/.csv
+    Export { format: Option<DumpFormat>, path: Option<String> },
+    // Write the grid on screen (data page or query result) as a Markdown table
+    ExportMarkdown { path: Option<String> },
+    // Write the SQL history to a file; the connection filter is filled in by App
+    // unless every connection was asked for
+    ExportHistory {
+        format: Option<HistoryFormat>,
+        path: Option<String>,
+        all_connections: bool,
+        filter: HistoryFilter,
+    },
+    // None removes the row limit
+    Limit(Option<usize>),
+    Sort { column: String, desc: bool },
+    Refresh,
+    Use(String),
+    Quit,
+}
+
+pub const COMMAND_HELP: &str = "export [csv|sql|md] [file], history [sql|json] [file] [all] [db=<name>] [since=<date>] [until=<date>], limit <n|none>, sort <column> [asc|desc], refresh, use <db>, q";
+
+pub fn parse(line: &str) -> Result<Command, String> {
+    let line = line.trim().trim_start_matches(':').trim();
+    let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    let args: Vec<&str> = rest.split_whitespace().collect();
+
+    match name.to_lowercase().as_str() {
+        "export" => {
+            if let Some(arg) = args.first().filter(|arg| ["md", "markdown"].contains(&arg.to_lowercase().as_str())) {
+                let path = rest[arg.len()..].trim();
+                return Ok(Command::ExportMarkdown { path: (!path.is_empty()).then(|| path.to_string()) });
+            }
+            let (format, path) = match args.first().map(|arg| arg.to_lowercase()) {
+                Some(arg) if arg == "csv" => (Some(DumpFormat::Csv), rest[3..].trim()),
+                Some(arg) if arg == "sql" => (Some(DumpFormat::Sql), rest[3..].trim()),
+                _ => (None, rest),
+            };
+            Ok(Command::Export {
+                format,
+                path: (!path.is_empty()).then(|| path.to_string()),
+            })
+        },
+        "history" => parse_history_export(&args),
+        "limit" => match args.as_slice() {
+            [value] if ["none", "off", "0"].contains(&value.to_lowercase().as_str()) => Ok(Command::Limit(None)),
+            [value] => value
+                .parse::<usize>()
+                .map(|limit| Command::Limit(Some(limit)))
+                .map_err(|_| format!("Not a row count: {}", value)),
+            _ => Err("Usage: limit <rows|none>".to_string()),
+        },
+        "sort" => {
+            let desc = match args.get(1).map(|arg| arg.to_lowercase()) {
+                None => false,
+                Some(direction) if direction == "asc" => false,
+                Some(direction) if direction == "desc" => true,
+                Some(direction) => return Err(format!("Sort direction must be asc or desc, not {}", direction)),
+            };
+            match args.as_slice() {
+                [column] | [column, _] => Ok(Command::Sort { column: column.to_string(), desc }),
+                _ => Err("Usage: sort <column> [asc|desc]".to_string()),
+            }
+        },
+        "refresh" | "r" => Ok(Command::Refresh),
+        "use" => match args.as_slice() {
+            [database] => Ok(Command::Use(database.trim_matches('`').to_string())),
+            _ => Err("Usage: use <database>".to_string()),
+        },
+        "q" | "quit" => Ok(Command::Quit),
+        "" => Err(format!("Commands: {}", COMMAND_HELP)),
+        other => Err(format!("Unknown command '{}' - commands: {}", other, COMMAND_HELP)),
+    }
+}
+
+// history [sql|json] [file] [all] [db=<name>] [since=YYYY-MM-DD] [until=YYYY-MM-DD]
+fn parse_history_export(args: &[&str]) -> Result<Command, String> {
+    let mut format = None;
+    let mut path: Vec<&str> = Vec::new();
+    let mut all_connections = false;
+    let mut filter = HistoryFilter::default();
+    
+    for (i, arg) in args.iter().enumerate() {
+        match arg.split_once('=') {
+            Some(("db", database)) => filter.database = Some(database.trim_matches('`').to_string()),
+            Some(("since", date)) => filter.since = Some(parse_date(date)?),
+            Some(("until", date)) => filter.until = Some(parse_date(date)?),
+            Some((option, _)) => return Err(format!("Unknown history option '{}' - use db=, since= or until=", option)),
+            None if i == 0 && arg.eq_ignore_ascii_case("sql") => format = Some(HistoryFormat::Sql),
+            None if i == 0 && arg.eq_ignore_ascii_case("json") => format = Some(HistoryFormat::Json),
+            None if arg.eq_ignore_ascii_case("all") => all_connections = true,
+            None => path.push(arg),
+        }
+    }
+    
+    Ok(Command::ExportHistory {
+        format,
+        path: (!path.is_empty()).then(|| path.join(" ")),
+        all_connections,
+        filter,
+    })
+}
+
+fn parse_date(text: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").map_err(|_| format!("Not a date (YYYY-MM-DD): {}", text))
+}
@@ -0,0 +1,83 @@
+// Tab completion for the SQL editor: keywords, tables and cached column names
+
+const SQL_KEYWORDS: &[&str] = &[
+    "ADD", "ALL", "ALTER", "AND", "AS", "ASC", "AUTO_INCREMENT", "AVG", "BETWEEN", "BY",
+    "CASE", "COLUMN", "COUNT", "CREATE", "CROSS", "DATABASE", "DEFAULT", "DELETE", "DESC",
+    "DESCRIBE", "DISTINCT", "DROP", "ELSE", "END", "EXISTS", "EXPLAIN", "FALSE", "FOREIGN",
+    "FROM", "FULL", "GROUP", "HAVING", "IN", "INDEX", "INNER", "INSERT", "INTERVAL", "INTO",
+    "IS", "JOIN", "KEY", "LEFT", "LIKE", "LIMIT", "MAX", "MIN", "NOT", "NULL", "OFFSET", "ON",
+    "OR", "ORDER", "OUTER", "PRIMARY", "REFERENCES", "REPLACE", "RIGHT", "SELECT", "SET",
+    "SHOW", "SUM", "TABLE", "TABLES", "THEN", "TRUE", "TRUNCATE", "UNION", "UNIQUE", "UPDATE",
+    "USE", "USING", "VALUES", "VIEW", "WHEN", "WHERE", "WITH",
+];
+
+#[derive(Debug, Clone)]
+pub struct Completion {
+    // Length in bytes of the partial word being replaced at the end of the input
+    pub word_len: usize,
+    pub candidates: Vec<String>,
+    pub selected: usize,
+}
+
+impl Completion {
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % self.candidates.len();
+    }
+
+    pub fn prev(&mut self) {
+        self.selected = self.selected.checked_sub(1).unwrap_or(self.candidates.len() - 1);
+    }
+
+    pub fn selected_candidate(&self) -> &str {
+        &self.candidates[self.selected]
+    }
+}
+
+// The identifier being typed at the end of the input (the editor's cursor)
+pub fn word_before_cursor(input: &str) -> &str {
+    let start = input
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .map(|i| i + input[i..].chars().next().map_or(1, char::len_utf8))
+        .unwrap_or(0);
+    &input[start..]
+}
+
+// Prefix matches, ignoring case: tables first, then columns, then keywords.
+// Keywords are completed in the case the user started typing them in.
+pub fn candidates<'a>(
+    prefix: &str,
+    tables: &'a [String],
+    columns: impl Iterator<Item = &'a String>,
+) -> Vec<String> {
+    if prefix.is_empty() {
+        return Vec::new();
+    }
+    let lower = prefix.to_lowercase();
+    let matches = |name: &str| name.to_lowercase().starts_with(&lower) && name.len() > prefix.len();
+    let lowercase_keywords = prefix.chars().all(|c| !c.is_uppercase());
+
+    let mut result: Vec<String> = Vec::new();
+    let names = tables.iter().chain(columns).map(|name| name.to_string());
+    let keywords = SQL_KEYWORDS.iter().map(|kw| {
+        if lowercase_keywords { kw.to_lowercase() } else { kw.to_string() }
+    });
+    for candidate in names.chain(keywords) {
+        if matches(&candidate) && !result.contains(&candidate) {
+            result.push(candidate);
+        }
+    }
+    result
+}
+
+// Tables the query already mentions, so only their columns need to be loaded
+pub fn referenced_tables<'a>(input: &str, tables: &'a [String]) -> Vec<&'a String> {
+    let words: Vec<String> = input
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect();
+    tables
+        .iter()
+        .filter(|table| words.contains(&table.to_lowercase()))
+        .collect()
+}
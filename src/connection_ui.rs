@@ -2,13 +2,19 @@ use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
     Frame,
 };
 
-use crate::connection_config::{ConnectionConfig, ConnectionManager};
+use std::collections::HashSet;
+
+use crate::connection_config::{
+    ConnectionConfig, ConnectionManager, BADGE_COLORS, COMMON_CHARSETS, COMMON_COLLATIONS, DEFAULT_CONNECT_TIMEOUT_SECS,
+    DEFAULT_POOL_MAX_CONNECTIONS, DEFAULT_POOL_MIN_CONNECTIONS, UNGROUPED,
+};
+use crate::theme::Theme;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionUIMode {
@@ -20,12 +26,65 @@ pub enum ConnectionUIMode {
 #[derive(Debug, Clone, PartialEq)]
 pub enum InputField {
     Name,
+    Group,
     Host,
     Port,
     Username,
     Password,
     Database,
     UseSSL,
+    CaCertPath,
+    ClientCertPath,
+    ClientKeyPath,
+    AcceptInvalidCerts,
+    Charset,
+    Collation,
+    KeymapProfile,
+    ReadOnly,
+    Color,
+    ConnectTimeout,
+    PoolMinConnections,
+    PoolMaxConnections,
+    SshHost,
+    SshPort,
+    SshUser,
+    SshKeyPath,
+}
+
+// Form order, used for Tab/Shift+Tab and for scrolling the form
+const FORM_FIELDS: &[InputField] = &[
+    InputField::Name,
+    InputField::Group,
+    InputField::Host,
+    InputField::Port,
+    InputField::Username,
+    InputField::Password,
+    InputField::Database,
+    InputField::UseSSL,
+    InputField::CaCertPath,
+    InputField::ClientCertPath,
+    InputField::ClientKeyPath,
+    InputField::AcceptInvalidCerts,
+    InputField::Charset,
+    InputField::Collation,
+    InputField::KeymapProfile,
+    InputField::ReadOnly,
+    InputField::Color,
+    InputField::ConnectTimeout,
+    InputField::PoolMinConnections,
+    InputField::PoolMaxConnections,
+    InputField::SshHost,
+    InputField::SshPort,
+    InputField::SshUser,
+    InputField::SshKeyPath,
+];
+
+// A line of the connection list
+enum ListEntry<'a> {
+    Root,
+    // Header of a group: its name, how many connections it holds and whether they are hidden
+    Group { name: String, count: usize, collapsed: bool },
+    Connection(&'a ConnectionConfig),
 }
 
 pub struct ConnectionUI {
@@ -35,10 +94,24 @@ pub struct ConnectionUI {
     pub temp_config: ConnectionConfig,
     pub show_password: bool,
     pub status_message: String,
+    pub theme: Theme,
+    // Narrows the list to connections whose name or host contains it (case-insensitive)
+    pub filter: String,
+    // Typed keys go to the filter until Enter or Esc
+    pub filter_editing: bool,
+    // Databases of the server the form points at, listed with Ctrl+L in the
+    // Database field; Tab completes against them. Dropped when the server changes
+    database_names: Option<Vec<String>>,
+    // Set by Ctrl+L; the caller connects and hands the names to set_database_names
+    database_lookup: Option<ConnectionConfig>,
+    // Groups whose connections are hidden under their header, for this session
+    collapsed_groups: HashSet<String>,
 }
 
+const LIST_HINT: &str = "Select a connection or create a new one";
+
 impl ConnectionUI {
-    pub fn new() -> Self {
+    pub fn new(theme: Theme) -> Self {
         let mut list_state = ListState::default();
         list_state.select(Some(0));
 
@@ -55,10 +128,44 @@ impl ConnectionUI {
                 None,
             ),
             show_password: false,
-            status_message: "Select a connection or create a new one".to_string(),
+            status_message: LIST_HINT.to_string(),
+            theme,
+            filter: String::new(),
+            filter_editing: false,
+            database_names: None,
+            database_lookup: None,
+            collapsed_groups: HashSet::new(),
+        }
+    }
+
+    pub fn select_last_used(&mut self, manager: &ConnectionManager) {
+        let last_used = match manager.get_last_used() {
+            Some(config) => config,
+            None => return,
+        };
+        
+        self.select_connection(&last_used.id, manager);
+    }
+
+    // Select a saved connection, opening its group if it is collapsed
+    fn select_connection(&mut self, id: &str, manager: &ConnectionManager) {
+        if let Some(config) = manager.list_connections().into_iter().find(|c| c.id == id) {
+            self.collapsed_groups.remove(config.group().unwrap_or(UNGROUPED));
+        }
+        let index = self
+            .list_entries(manager)
+            .iter()
+            .position(|entry| matches!(entry, ListEntry::Connection(c) if c.id == id));
+        if let Some(index) = index {
+            self.list_state.select(Some(index));
         }
     }
 
+    // Whether typed characters currently go to the filter rather than to list commands like q
+    pub fn is_editing_filter(&self) -> bool {
+        self.mode == ConnectionUIMode::List && self.filter_editing
+    }
+
     pub fn draw(&mut self, f: &mut Frame, manager: &ConnectionManager) {
         let size = f.area();
         
@@ -83,25 +190,35 @@ impl ConnectionUI {
 
         // Title
         let title = Paragraph::new("RMSQL - Connection Manager")
-            .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+            .style(Style::default().fg(self.theme.header).add_modifier(Modifier::BOLD))
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
         f.render_widget(title, chunks[0]);
 
-        // Connection list
-        let connections = manager.list_connections();
+        // Connection list: the root entry, then the saved connections, under
+        // group headers once any connection has a group
+        let entries = self.list_entries(manager);
+        let grouped = entries.iter().any(|entry| matches!(entry, ListEntry::Group { .. }));
         let mut items = Vec::new();
 
-        // Add root connection option if running as root
-        if Self::is_running_as_root() {
-            items.push(ListItem::new(Line::from(vec![
-                Span::styled("⚡ ", Style::default().fg(Color::Yellow)),
-                Span::raw("Root (Auto-detect)"),
-            ])));
-        }
-
-        // Add saved connections
-        for config in &connections {
+        for entry in &entries {
+            let config = match entry {
+                ListEntry::Root => {
+                    items.push(ListItem::new(Line::from(vec![
+                        Span::styled("⚡ ", Style::default().fg(self.theme.accent)),
+                        Span::raw("Root (Auto-detect)"),
+                    ])));
+                    continue;
+                }
+                ListEntry::Group { name, count, collapsed } => {
+                    items.push(ListItem::new(Line::from(Span::styled(
+                        format!("{} {} ({})", if *collapsed { "▸" } else { "▾" }, name, count),
+                        Style::default().fg(self.theme.header).add_modifier(Modifier::BOLD),
+                    ))));
+                    continue;
+                }
+                ListEntry::Connection(config) => config,
+            };
             let marker = if manager.get_last_used().map(|c| &c.id) == Some(&config.id) {
                 "★ "
             } else {
@@ -109,7 +226,8 @@ impl ConnectionUI {
             };
             
             items.push(ListItem::new(Line::from(vec![
-                Span::styled(marker, Style::default().fg(Color::Yellow)),
+                Span::raw(if grouped { "  " } else { "" }),
+                Span::styled(marker, Style::default().fg(self.theme.accent)),
                 Span::raw(&config.name),
                 Span::styled(
                     format!(" ({}:{}@{}:{})", 
@@ -118,25 +236,35 @@ impl ConnectionUI {
                         config.host, 
                         config.port
                     ),
-                    Style::default().fg(Color::Gray)
+                    Style::default().fg(self.theme.muted)
                 ),
             ])));
         }
 
         if items.is_empty() {
-            items.push(ListItem::new("No connections configured"));
+            items.push(ListItem::new(if self.filter.is_empty() {
+                "No connections configured"
+            } else {
+                "No connections match the filter"
+            }));
         }
 
+        let order = if manager.sort_by_last_used { "last used" } else { "name" };
+        let mut list_title = format!("Connections (by {})", order);
+        if self.filter_editing || !self.filter.is_empty() {
+            let cursor = if self.filter_editing { "_" } else { "" };
+            list_title.push_str(&format!(" - filter: {}{}", self.filter, cursor));
+        }
         let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title("Connections"))
-            .highlight_style(Style::default().bg(Color::Blue).fg(Color::White))
+            .block(Block::default().borders(Borders::ALL).title(list_title))
+            .highlight_style(Style::default().bg(self.theme.highlight_bg).fg(self.theme.highlight_fg))
             .highlight_symbol("> ");
 
         f.render_stateful_widget(list, chunks[1], &mut self.list_state);
 
         // Status message
         let status = Paragraph::new(self.status_message.clone())
-            .style(Style::default().fg(Color::Yellow))
+            .style(Style::default().fg(self.theme.accent))
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
         f.render_widget(status, chunks[2]);
@@ -144,20 +272,26 @@ impl ConnectionUI {
         // Help
         let help_text = vec![
             Line::from(vec![
-                Span::styled("Enter", Style::default().fg(Color::Green)),
+                Span::styled("Enter", Style::default().fg(self.theme.success)),
                 Span::raw(": Connect | "),
-                Span::styled("n", Style::default().fg(Color::Green)),
+                Span::styled("n", Style::default().fg(self.theme.success)),
                 Span::raw(": New | "),
-                Span::styled("e", Style::default().fg(Color::Green)),
+                Span::styled("e", Style::default().fg(self.theme.success)),
                 Span::raw(": Edit | "),
-                Span::styled("d", Style::default().fg(Color::Green)),
+                Span::styled("d", Style::default().fg(self.theme.success)),
                 Span::raw(": Delete | "),
-                Span::styled("q", Style::default().fg(Color::Green)),
+                Span::styled("s", Style::default().fg(self.theme.success)),
+                Span::raw(": Sort by name/last used | "),
+                Span::styled("q", Style::default().fg(self.theme.success)),
                 Span::raw(": Quit"),
             ]),
             Line::from(vec![
-                Span::styled("↑↓", Style::default().fg(Color::Green)),
-                Span::raw(": Navigate"),
+                Span::styled("↑↓/jk", Style::default().fg(self.theme.success)),
+                Span::raw(": Navigate | "),
+                Span::styled("Enter/Space", Style::default().fg(self.theme.success)),
+                Span::raw(" on a group: Open/close | "),
+                Span::styled("/", Style::default().fg(self.theme.success)),
+                Span::raw(": Filter by name/host (Enter keeps it, Esc clears)"),
             ]),
         ];
 
@@ -176,7 +310,7 @@ impl ConnectionUI {
             .constraints([
                 Constraint::Length(3),
                 Constraint::Min(10),
-                Constraint::Length(3),
+                Constraint::Length(5),
             ])
             .split(popup_area);
 
@@ -188,64 +322,115 @@ impl ConnectionUI {
         };
 
         let title_widget = Paragraph::new(title)
-            .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+            .style(Style::default().fg(self.theme.header).add_modifier(Modifier::BOLD))
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
         f.render_widget(title_widget, chunks[0]);
 
-        // Form fields
-        let form_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Length(3),
-                Constraint::Length(3),
-            ])
-            .split(chunks[1]);
-
-        self.draw_input_field(f, form_chunks[0], "Name", &self.temp_config.name, &InputField::Name);
-        self.draw_input_field(f, form_chunks[1], "Host", &self.temp_config.host, &InputField::Host);
-        self.draw_input_field(f, form_chunks[2], "Port", &self.temp_config.port.to_string(), &InputField::Port);
-        self.draw_input_field(f, form_chunks[3], "Username", &self.temp_config.username, &InputField::Username);
-        
+        // Form fields, scrolled so the selected one stays visible on small terminals
+        let port_display = self.temp_config.port.to_string();
         let password_display = if self.show_password { 
             self.temp_config.password.clone() 
         } else { 
             "*".repeat(self.temp_config.password.len()) 
         };
-        self.draw_input_field(f, form_chunks[4], "Password", &password_display, &InputField::Password);
+        let ssh_port_display = self.temp_config.ssh_port.map(|p| p.to_string()).unwrap_or_default();
+        let timeout_display = self.temp_config.connect_timeout_secs.map(|s| s.to_string()).unwrap_or_default();
+        let pool_min_display = self.temp_config.pool_min_connections.map(|n| n.to_string()).unwrap_or_default();
+        let pool_max_display = self.temp_config.pool_max_connections.map(|n| n.to_string()).unwrap_or_default();
+        let timeout_label = format!("Connect timeout in seconds (empty = {})", DEFAULT_CONNECT_TIMEOUT_SECS);
+        let pool_min_label = format!("Min pool connections (empty = {})", DEFAULT_POOL_MIN_CONNECTIONS);
+        let pool_max_label = format!("Max pool connections (empty = {})", DEFAULT_POOL_MAX_CONNECTIONS);
+        let fields: Vec<(&str, &str, InputField)> = vec![
+            ("Name", &self.temp_config.name, InputField::Name),
+            ("Group (optional, e.g. Prod)", self.temp_config.group.as_deref().unwrap_or(""), InputField::Group),
+            ("Host", &self.temp_config.host, InputField::Host),
+            ("Port", &port_display, InputField::Port),
+            ("Username", &self.temp_config.username, InputField::Username),
+            ("Password", &password_display, InputField::Password),
+            ("Database (optional)", self.temp_config.default_database.as_deref().unwrap_or(""), InputField::Database),
+            ("Use SSL", if self.temp_config.use_ssl { "Yes" } else { "No" }, InputField::UseSSL),
+            ("CA certificate file (optional, PEM)", self.temp_config.ca_cert_path.as_deref().unwrap_or(""), InputField::CaCertPath),
+            (
+                "Client certificate file (optional, PEM, for mutual TLS)",
+                self.temp_config.client_cert_path.as_deref().unwrap_or(""),
+                InputField::ClientCertPath,
+            ),
+            ("Client key file (optional, PEM)", self.temp_config.client_key_path.as_deref().unwrap_or(""), InputField::ClientKeyPath),
+            (
+                "Accept invalid/self-signed certificates (insecure)",
+                if self.temp_config.accept_invalid_certs { "Yes" } else { "No" },
+                InputField::AcceptInvalidCerts,
+            ),
+            (
+                "Charset (Space to cycle common ones, empty = from the collation, else utf8mb4)",
+                self.temp_config.charset.as_deref().unwrap_or(""),
+                InputField::Charset,
+            ),
+            (
+                "Collation (Space to cycle common ones, empty = utf8mb4_unicode_ci, or the charset's default)",
+                self.temp_config.collation.as_deref().unwrap_or(""),
+                InputField::Collation,
+            ),
+            (
+                "Keymap profile (optional, from user_config.json)",
+                self.temp_config.keymap_profile.as_deref().unwrap_or(""),
+                InputField::KeymapProfile,
+            ),
+            (
+                "Read-only (block statements that write)",
+                if self.temp_config.read_only { "Yes" } else { "No" },
+                InputField::ReadOnly,
+            ),
+            (
+                "Header badge color (Space to cycle red/yellow/green/blue/magenta/cyan, empty = neutral)",
+                self.temp_config.color.as_deref().unwrap_or(""),
+                InputField::Color,
+            ),
+            (&timeout_label, &timeout_display, InputField::ConnectTimeout),
+            (&pool_min_label, &pool_min_display, InputField::PoolMinConnections),
+            (&pool_max_label, &pool_max_display, InputField::PoolMaxConnections),
+            (
+                "SSH tunnel host (optional, empty = connect directly)",
+                self.temp_config.ssh_host.as_deref().unwrap_or(""),
+                InputField::SshHost,
+            ),
+            ("SSH port (empty = 22)", &ssh_port_display, InputField::SshPort),
+            ("SSH user (empty = ssh default)", self.temp_config.ssh_user.as_deref().unwrap_or(""), InputField::SshUser),
+            ("SSH key file (optional)", self.temp_config.ssh_key_path.as_deref().unwrap_or(""), InputField::SshKeyPath),
+        ];
         
-        self.draw_input_field(
-            f, 
-            form_chunks[5], 
-            "Database (optional)", 
-            &self.temp_config.default_database.as_deref().unwrap_or(""),
-            &InputField::Database
-        );
-
-        let ssl_display = if self.temp_config.use_ssl { "Yes" } else { "No" };
-        self.draw_input_field(f, form_chunks[6], "Use SSL", ssl_display, &InputField::UseSSL);
+        let visible = ((chunks[1].height / 3) as usize).clamp(1, fields.len());
+        let selected = FORM_FIELDS.iter().position(|f| *f == self.input_field).unwrap_or(0);
+        let first = (selected + 1).saturating_sub(visible);
+        let form_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(3); visible])
+            .split(chunks[1]);
+        
+        for (area, (label, value, field)) in form_chunks.iter().zip(fields.iter().skip(first)) {
+            self.draw_input_field(f, *area, label, value, field);
+        }
 
         // Help
         let help_text = vec![
             Line::from(vec![
-                Span::styled("Tab/Shift+Tab", Style::default().fg(Color::Green)),
+                Span::styled("Tab/Shift+Tab", Style::default().fg(self.theme.success)),
                 Span::raw(": Navigate fields | "),
-                Span::styled("Ctrl+S", Style::default().fg(Color::Green)),
+                Span::styled("Ctrl+S", Style::default().fg(self.theme.success)),
                 Span::raw(": Save | "),
-                Span::styled("Esc", Style::default().fg(Color::Green)),
+                Span::styled("Ctrl+L", Style::default().fg(self.theme.success)),
+                Span::raw(": List databases (Database field) | "),
+                Span::styled("Esc", Style::default().fg(self.theme.success)),
                 Span::raw(": Cancel"),
             ]),
             Line::from(vec![
-                Span::styled("Ctrl+P", Style::default().fg(Color::Green)),
+                Span::styled("Ctrl+P", Style::default().fg(self.theme.success)),
                 Span::raw(": Toggle password visibility | "),
-                Span::styled("Space", Style::default().fg(Color::Green)),
-                Span::raw(": Toggle SSL (on SSL field)"),
+                Span::styled("Space", Style::default().fg(self.theme.success)),
+                Span::raw(": Toggle Yes/No fields / cycle charset, collation and color"),
             ]),
+            Line::styled(self.status_message.clone(), Style::default().fg(self.theme.accent)),
         ];
 
         let help = Paragraph::new(help_text)
@@ -258,7 +443,7 @@ impl ConnectionUI {
         let is_selected = &self.input_field == field;
         
         let style = if is_selected {
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD)
         } else {
             Style::default()
         };
@@ -267,7 +452,7 @@ impl ConnectionUI {
             .borders(Borders::ALL)
             .title(label)
             .border_style(if is_selected { 
-                Style::default().fg(Color::Yellow) 
+                Style::default().fg(self.theme.accent) 
             } else { 
                 Style::default() 
             });
@@ -289,8 +474,39 @@ impl ConnectionUI {
     }
 
     fn handle_list_key(&mut self, key: KeyEvent, manager: &mut ConnectionManager) -> Result<Option<ConnectionConfig>> {
+        if self.filter_editing {
+            match key.code {
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.reset_list_selection(manager);
+                    return Ok(None);
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.reset_list_selection(manager);
+                    return Ok(None);
+                }
+                KeyCode::Enter => {
+                    self.filter_editing = false;
+                    return Ok(None);
+                }
+                KeyCode::Esc => {
+                    self.clear_filter(manager);
+                    return Ok(None);
+                }
+                // Arrows still move through the narrowed list
+                _ => {}
+            }
+        }
+        
         match key.code {
-            KeyCode::Up => {
+            KeyCode::Char('/') => {
+                self.filter_editing = true;
+            }
+            KeyCode::Esc if !self.filter.is_empty() => {
+                self.clear_filter(manager);
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
                 let i = match self.list_state.selected() {
                     Some(i) => {
                         if i == 0 {
@@ -303,7 +519,7 @@ impl ConnectionUI {
                 };
                 self.list_state.select(Some(i));
             }
-            KeyCode::Down => {
+            KeyCode::Down | KeyCode::Char('j') => {
                 let i = match self.list_state.selected() {
                     Some(i) => {
                         if i >= self.get_total_connections(manager).saturating_sub(1) {
@@ -316,6 +532,8 @@ impl ConnectionUI {
                 };
                 self.list_state.select(Some(i));
             }
+            // On a group header Enter and Space open or close the group
+            KeyCode::Enter | KeyCode::Char(' ') if self.toggle_selected_group(manager) => {}
             KeyCode::Enter => {
                 if let Some(selected) = self.list_state.selected() {
                     return Ok(self.get_selected_connection(selected, manager));
@@ -324,6 +542,7 @@ impl ConnectionUI {
             KeyCode::Char('n') => {
                 self.mode = ConnectionUIMode::NewConnection;
                 self.reset_temp_config();
+                self.status_message.clear();
             }
             KeyCode::Char('e') => {
                 if let Some(selected) = self.list_state.selected() {
@@ -332,9 +551,28 @@ impl ConnectionUI {
                         let config_clone = config.clone();
                         self.mode = ConnectionUIMode::EditConnection(config_id);
                         self.temp_config = config_clone;
+                        self.database_names = None;
+                        self.status_message.clear();
                     }
                 }
             }
+            KeyCode::Char('s') => {
+                // Keep the same connection selected in the new order
+                let selected_id = self
+                    .list_state
+                    .selected()
+                    .and_then(|selected| self.get_connection_by_index(selected, manager))
+                    .map(|config| config.id.clone());
+                manager.toggle_sort()?;
+                if let Some(id) = selected_id {
+                    self.select_connection(&id, manager);
+                }
+                self.status_message = if manager.sort_by_last_used {
+                    "Connections sorted by last use".to_string()
+                } else {
+                    "Connections sorted by name".to_string()
+                };
+            }
             KeyCode::Char('d') => {
                 if let Some(selected) = self.list_state.selected() {
                     if let Some(config) = self.get_connection_by_index(selected, manager) {
@@ -362,6 +600,10 @@ impl ConnectionUI {
         match key.code {
             KeyCode::Esc => {
                 self.mode = ConnectionUIMode::List;
+                self.status_message = LIST_HINT.to_string();
+            }
+            KeyCode::Tab if self.input_field == InputField::Database && self.database_names.is_some() => {
+                self.complete_database();
             }
             KeyCode::Tab => {
                 self.next_field();
@@ -377,17 +619,34 @@ impl ConnectionUI {
                     'p' => {
                         self.show_password = !self.show_password;
                     }
+                    'l' if self.input_field == InputField::Database => {
+                        self.request_database_lookup();
+                    }
                     _ => {}
                 }
             }
-            KeyCode::Enter => {
-                if self.input_field == InputField::UseSSL {
-                    self.temp_config.use_ssl = !self.temp_config.use_ssl;
-                }
+            KeyCode::Enter if self.input_field == InputField::UseSSL => {
+                self.temp_config.use_ssl = !self.temp_config.use_ssl;
+            }
+            KeyCode::Enter if self.input_field == InputField::AcceptInvalidCerts => {
+                self.temp_config.accept_invalid_certs = !self.temp_config.accept_invalid_certs;
+            }
+            KeyCode::Enter if self.input_field == InputField::ReadOnly => {
+                self.temp_config.read_only = !self.temp_config.read_only;
             }
             KeyCode::Char(c) => {
                 if c == ' ' && self.input_field == InputField::UseSSL {
                     self.temp_config.use_ssl = !self.temp_config.use_ssl;
+                } else if c == ' ' && self.input_field == InputField::AcceptInvalidCerts {
+                    self.temp_config.accept_invalid_certs = !self.temp_config.accept_invalid_certs;
+                } else if c == ' ' && self.input_field == InputField::ReadOnly {
+                    self.temp_config.read_only = !self.temp_config.read_only;
+                } else if c == ' ' && self.input_field == InputField::Charset {
+                    self.cycle_charset();
+                } else if c == ' ' && self.input_field == InputField::Collation {
+                    self.cycle_collation();
+                } else if c == ' ' && self.input_field == InputField::Color {
+                    self.cycle_color();
                 } else {
                     self.input_char(c);
                 }
@@ -400,48 +659,105 @@ impl ConnectionUI {
         Ok(None)
     }
 
-    fn get_total_connections(&self, manager: &ConnectionManager) -> usize {
-        let mut count = manager.list_connections().len();
-        if Self::is_running_as_root() {
-            count += 1;
+    // Saved connections matching the filter, in list_connections order
+    fn visible_connections<'a>(&self, manager: &'a ConnectionManager) -> Vec<&'a ConnectionConfig> {
+        let filter = self.filter.to_lowercase();
+        manager
+            .list_connections()
+            .into_iter()
+            .filter(|c| filter.is_empty() || c.name.to_lowercase().contains(&filter) || c.host.to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    // The root entry is listed when running as root, unless the filter rules it out
+    fn shows_root(&self) -> bool {
+        Self::is_running_as_root() && (self.filter.is_empty() || "root".contains(&self.filter.to_lowercase()))
+    }
+
+    fn reset_list_selection(&mut self, manager: &ConnectionManager) {
+        let total = self.get_total_connections(manager);
+        self.list_state.select((total > 0).then_some(0));
+    }
+
+    // Drop the filter, keeping the selected connection selected in the full list
+    fn clear_filter(&mut self, manager: &ConnectionManager) {
+        let selected_id = self
+            .list_state
+            .selected()
+            .and_then(|selected| self.get_connection_by_index(selected, manager))
+            .map(|config| config.id.clone());
+        self.filter.clear();
+        self.filter_editing = false;
+        self.reset_list_selection(manager);
+        if let Some(id) = selected_id {
+            self.select_connection(&id, manager);
         }
-        count
     }
 
-    fn get_selected_connection(&self, index: usize, manager: &ConnectionManager) -> Option<ConnectionConfig> {
-        let mut current_index = 0;
+    // Lines of the connection list: the root entry when shown, then the visible
+    // connections. Once any of them has a group they are listed by group (by name,
+    // UNGROUPED last) under headers; a collapsed group shows only its header, unless
+    // a filter is typed.
+    fn list_entries<'a>(&self, manager: &'a ConnectionManager) -> Vec<ListEntry<'a>> {
+        let mut entries = Vec::new();
+        if self.shows_root() {
+            entries.push(ListEntry::Root);
+        }
+        
+        let connections = self.visible_connections(manager);
+        if connections.iter().all(|c| c.group().is_none()) {
+            entries.extend(connections.into_iter().map(ListEntry::Connection));
+            return entries;
+        }
         
-        // Check root connection first
-        if Self::is_running_as_root() {
-            if index == current_index {
-                return Some(ConnectionManager::create_root_connection());
+        let mut groups: Vec<&str> = connections.iter().filter_map(|c| c.group()).collect();
+        groups.sort_unstable();
+        groups.dedup();
+        for group in groups.into_iter().map(Some).chain([None]) {
+            let members: Vec<&ConnectionConfig> = connections.iter().copied().filter(|c| c.group() == group).collect();
+            if members.is_empty() {
+                continue;
+            }
+            let name = group.unwrap_or(UNGROUPED).to_string();
+            let collapsed = self.filter.is_empty() && self.collapsed_groups.contains(&name);
+            entries.push(ListEntry::Group { name, count: members.len(), collapsed });
+            if !collapsed {
+                entries.extend(members.into_iter().map(ListEntry::Connection));
             }
-            current_index += 1;
         }
+        entries
+    }
 
-        // Check saved connections
-        let connections = manager.list_connections();
-        if let Some(config) = connections.get(index - current_index) {
-            return Some((*config).clone());
+    // Open or close the group whose header is selected; false when no header is
+    fn toggle_selected_group(&mut self, manager: &ConnectionManager) -> bool {
+        let entries = self.list_entries(manager);
+        let Some(ListEntry::Group { name, .. }) = self.list_state.selected().and_then(|i| entries.get(i)) else {
+            return false;
+        };
+        if !self.collapsed_groups.remove(name) {
+            self.collapsed_groups.insert(name.clone());
         }
+        true
+    }
 
-        None
+    fn get_total_connections(&self, manager: &ConnectionManager) -> usize {
+        self.list_entries(manager).len()
     }
 
-    fn get_connection_by_index<'a>(&self, index: usize, manager: &'a ConnectionManager) -> Option<&'a ConnectionConfig> {
-        let mut current_index = 0;
-        
-        // Skip root connection
-        if Self::is_running_as_root() {
-            if index == current_index {
-                return None; // Can't edit root connection
-            }
-            current_index += 1;
+    fn get_selected_connection(&self, index: usize, manager: &ConnectionManager) -> Option<ConnectionConfig> {
+        match self.list_entries(manager).get(index)? {
+            ListEntry::Root => Some(ConnectionManager::create_root_connection()),
+            ListEntry::Connection(config) => Some((*config).clone()),
+            ListEntry::Group { .. } => None,
         }
+    }
 
-        // Get saved connections
-        let connections = manager.list_connections();
-        connections.get(index - current_index).copied()
+    // Saved connections only: the root entry and group headers can't be edited or deleted
+    fn get_connection_by_index<'a>(&self, index: usize, manager: &'a ConnectionManager) -> Option<&'a ConnectionConfig> {
+        match self.list_entries(manager).get(index)? {
+            ListEntry::Connection(config) => Some(config),
+            _ => None,
+        }
     }
 
     fn is_running_as_root() -> bool {
@@ -460,6 +776,73 @@ impl ConnectionUI {
             None,
         );
         self.input_field = InputField::Name;
+        self.database_names = None;
+    }
+    
+    // Ask the caller to connect with the form as it is, once the fields a login
+    // needs are filled in
+    fn request_database_lookup(&mut self) {
+        if self.temp_config.host.trim().is_empty() {
+            self.status_message = "Host is required to list databases".to_string();
+        } else if self.temp_config.username.trim().is_empty() {
+            self.status_message = "Username is required to list databases".to_string();
+        } else {
+            self.status_message = format!("Listing databases on {}:{}...", self.temp_config.host, self.temp_config.port);
+            self.database_lookup = Some(self.temp_config.clone());
+        }
+    }
+    
+    pub fn take_database_lookup(&mut self) -> Option<ConnectionConfig> {
+        self.database_lookup.take()
+    }
+    
+    // The result of a lookup; on failure the field stays as typed
+    pub fn set_database_names(&mut self, result: Result<Vec<String>>) {
+        match result {
+            Ok(names) => {
+                self.status_message = format!("{} databases on the server - Tab completes", names.len());
+                self.database_names = Some(names);
+            }
+            Err(e) => {
+                self.status_message = format!("Could not list databases: {:#}", e);
+                self.database_names = None;
+            }
+        }
+    }
+    
+    // Extend the typed name to the longest prefix shared by the databases starting with
+    // it, listing them when there is more than one. Moves on to the next field when the
+    // name is already complete or nothing matches.
+    fn complete_database(&mut self) {
+        let Some(names) = &self.database_names else {
+            return;
+        };
+        let typed = self.temp_config.default_database.clone().unwrap_or_default();
+        let matches: Vec<&String> = names.iter().filter(|name| name.starts_with(&typed)).collect();
+        if matches.is_empty() || names.contains(&typed) {
+            self.next_field();
+            return;
+        }
+        
+        let mut common = matches[0].as_str();
+        for name in &matches[1..] {
+            let shared = common.char_indices()
+                .zip(name.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(common.len().min(name.len()), |((i, _), _)| i);
+            common = &common[..shared];
+        }
+        // Nothing left to extend: take the first candidate so Tab always makes progress
+        let completed = if common.len() > typed.len() { common } else { matches[0].as_str() };
+        self.status_message = match matches.len() {
+            1 => format!("Database: {}", completed),
+            count => format!(
+                "{} matches: {}",
+                count,
+                matches.iter().map(|name| name.as_str()).collect::<Vec<_>>().join(", ")
+            ),
+        };
+        self.temp_config.default_database = Some(completed.to_string());
     }
 
     fn save_connection(&mut self, manager: &mut ConnectionManager) -> Result<Option<ConnectionConfig>> {
@@ -494,32 +877,31 @@ impl ConnectionUI {
     }
 
     fn next_field(&mut self) {
-        self.input_field = match self.input_field {
-            InputField::Name => InputField::Host,
-            InputField::Host => InputField::Port,
-            InputField::Port => InputField::Username,
-            InputField::Username => InputField::Password,
-            InputField::Password => InputField::Database,
-            InputField::Database => InputField::UseSSL,
-            InputField::UseSSL => InputField::Name,
-        };
+        let index = FORM_FIELDS.iter().position(|f| *f == self.input_field).unwrap_or(0);
+        self.input_field = FORM_FIELDS[(index + 1) % FORM_FIELDS.len()].clone();
     }
 
     fn prev_field(&mut self) {
-        self.input_field = match self.input_field {
-            InputField::Name => InputField::UseSSL,
-            InputField::Host => InputField::Name,
-            InputField::Port => InputField::Host,
-            InputField::Username => InputField::Port,
-            InputField::Password => InputField::Username,
-            InputField::Database => InputField::Password,
-            InputField::UseSSL => InputField::Database,
-        };
+        let index = FORM_FIELDS.iter().position(|f| *f == self.input_field).unwrap_or(0);
+        self.input_field = FORM_FIELDS[(index + FORM_FIELDS.len() - 1) % FORM_FIELDS.len()].clone();
+    }
+
+    // Whether editing the field changes which server or login the form points at
+    fn changes_server(&self) -> bool {
+        matches!(
+            self.input_field,
+            InputField::Host | InputField::Port | InputField::Username | InputField::Password
+                | InputField::SshHost | InputField::SshPort | InputField::SshUser | InputField::SshKeyPath
+        )
     }
 
     fn input_char(&mut self, c: char) {
+        if self.changes_server() {
+            self.database_names = None;
+        }
         match self.input_field {
             InputField::Name => self.temp_config.name.push(c),
+            InputField::Group => self.temp_config.group.get_or_insert_with(String::new).push(c),
             InputField::Host => self.temp_config.host.push(c),
             InputField::Port => {
                 if c.is_ascii_digit() {
@@ -549,12 +931,100 @@ impl ConnectionUI {
                     _ => {}
                 }
             }
+            InputField::CaCertPath => {
+                self.temp_config.ca_cert_path.get_or_insert_with(String::new).push(c);
+            }
+            InputField::ClientCertPath => {
+                self.temp_config.client_cert_path.get_or_insert_with(String::new).push(c);
+            }
+            InputField::ClientKeyPath => {
+                self.temp_config.client_key_path.get_or_insert_with(String::new).push(c);
+            }
+            InputField::AcceptInvalidCerts => {
+                match c.to_ascii_lowercase() {
+                    'y' => self.temp_config.accept_invalid_certs = true,
+                    'n' => self.temp_config.accept_invalid_certs = false,
+                    _ => {}
+                }
+            }
+            InputField::Charset => {
+                self.temp_config.charset.get_or_insert_with(String::new).push(c);
+            }
+            InputField::Collation => {
+                self.temp_config.collation.get_or_insert_with(String::new).push(c);
+            }
+            InputField::KeymapProfile => {
+                self.temp_config.keymap_profile.get_or_insert_with(String::new).push(c);
+            }
+            InputField::ReadOnly => {
+                match c.to_ascii_lowercase() {
+                    'y' => self.temp_config.read_only = true,
+                    'n' => self.temp_config.read_only = false,
+                    _ => {}
+                }
+            }
+            InputField::Color => {
+                self.temp_config.color.get_or_insert_with(String::new).push(c);
+            }
+            InputField::ConnectTimeout => push_optional_digit(&mut self.temp_config.connect_timeout_secs, c),
+            InputField::PoolMinConnections => push_optional_digit(&mut self.temp_config.pool_min_connections, c),
+            InputField::PoolMaxConnections => push_optional_digit(&mut self.temp_config.pool_max_connections, c),
+            InputField::SshHost => {
+                self.temp_config.ssh_host.get_or_insert_with(String::new).push(c);
+            }
+            InputField::SshPort => {
+                if c.is_ascii_digit() {
+                    let port_str = format!("{}{}", self.temp_config.ssh_port.map(|p| p.to_string()).unwrap_or_default(), c);
+                    if let Ok(port) = port_str.parse::<u16>() {
+                        self.temp_config.ssh_port = Some(port);
+                    }
+                }
+            }
+            InputField::SshUser => {
+                self.temp_config.ssh_user.get_or_insert_with(String::new).push(c);
+            }
+            InputField::SshKeyPath => {
+                self.temp_config.ssh_key_path.get_or_insert_with(String::new).push(c);
+            }
         }
     }
+    
+    fn cycle_charset(&mut self) {
+        let current = self.temp_config.charset.as_deref().unwrap_or("");
+        let next = match COMMON_CHARSETS.iter().position(|c| *c == current) {
+            Some(i) => COMMON_CHARSETS[(i + 1) % COMMON_CHARSETS.len()],
+            None => COMMON_CHARSETS[0],
+        };
+        self.temp_config.charset = Some(next.to_string());
+    }
+    
+    fn cycle_collation(&mut self) {
+        // Move to the next preset, starting from the first when the value is custom or empty
+        let current = self.temp_config.collation.as_deref().unwrap_or("");
+        let next = match COMMON_COLLATIONS.iter().position(|c| *c == current) {
+            Some(i) => COMMON_COLLATIONS[(i + 1) % COMMON_COLLATIONS.len()],
+            None => COMMON_COLLATIONS[0],
+        };
+        self.temp_config.collation = Some(next.to_string());
+    }
 
+    // Next palette color; after the last one the badge goes back to neutral
+    fn cycle_color(&mut self) {
+        let current = self.temp_config.color.as_deref().unwrap_or("");
+        self.temp_config.color = match BADGE_COLORS.iter().position(|c| *c == current) {
+            Some(i) if i + 1 == BADGE_COLORS.len() => None,
+            Some(i) => Some(BADGE_COLORS[i + 1].to_string()),
+            None => Some(BADGE_COLORS[0].to_string()),
+        };
+    }
+    
     fn delete_char(&mut self) {
+        if self.changes_server() {
+            self.database_names = None;
+        }
         match self.input_field {
             InputField::Name => { self.temp_config.name.pop(); }
+            InputField::Group => pop_optional(&mut self.temp_config.group),
             InputField::Host => { self.temp_config.host.pop(); }
             InputField::Port => {
                 let mut port_str = self.temp_config.port.to_string();
@@ -579,6 +1049,43 @@ impl ConnectionUI {
                 // Toggle SSL on backspace
                 self.temp_config.use_ssl = !self.temp_config.use_ssl;
             }
+            InputField::CaCertPath => pop_optional(&mut self.temp_config.ca_cert_path),
+            InputField::ClientCertPath => pop_optional(&mut self.temp_config.client_cert_path),
+            InputField::ClientKeyPath => pop_optional(&mut self.temp_config.client_key_path),
+            InputField::AcceptInvalidCerts => {
+                self.temp_config.accept_invalid_certs = !self.temp_config.accept_invalid_certs;
+            }
+            InputField::Charset => pop_optional(&mut self.temp_config.charset),
+            InputField::Collation => {
+                if let Some(ref mut collation) = self.temp_config.collation {
+                    collation.pop();
+                    if collation.is_empty() {
+                        self.temp_config.collation = None;
+                    }
+                }
+            }
+            InputField::KeymapProfile => {
+                if let Some(ref mut profile) = self.temp_config.keymap_profile {
+                    profile.pop();
+                    if profile.is_empty() {
+                        self.temp_config.keymap_profile = None;
+                    }
+                }
+            }
+            InputField::ReadOnly => {
+                self.temp_config.read_only = !self.temp_config.read_only;
+            }
+            InputField::Color => pop_optional(&mut self.temp_config.color),
+            InputField::ConnectTimeout => pop_optional_digit(&mut self.temp_config.connect_timeout_secs),
+            InputField::PoolMinConnections => pop_optional_digit(&mut self.temp_config.pool_min_connections),
+            InputField::PoolMaxConnections => pop_optional_digit(&mut self.temp_config.pool_max_connections),
+            InputField::SshHost => pop_optional(&mut self.temp_config.ssh_host),
+            InputField::SshPort => {
+                let port_str = self.temp_config.ssh_port.map(|p| p.to_string()).unwrap_or_default();
+                self.temp_config.ssh_port = port_str[..port_str.len().saturating_sub(1)].parse().ok();
+            }
+            InputField::SshUser => pop_optional(&mut self.temp_config.ssh_user),
+            InputField::SshKeyPath => pop_optional(&mut self.temp_config.ssh_key_path),
         }
     }
 
@@ -605,6 +1112,32 @@ impl ConnectionUI {
 
 impl Default for ConnectionUI {
     fn default() -> Self {
-        Self::new()
+        Self::new(Theme::default())
     }
 }
+
+// Remove the last character of an optional text field, clearing it when it becomes empty
+fn pop_optional(value: &mut Option<String>) {
+    if let Some(text) = value {
+        text.pop();
+        if text.is_empty() {
+            *value = None;
+        }
+    }
+}
+
+// Append a digit to an optional number, ignoring other characters and overflow
+fn push_optional_digit<T: std::str::FromStr + ToString>(value: &mut Option<T>, c: char) {
+    if c.is_ascii_digit() {
+        let text = format!("{}{}", value.as_ref().map(|v| v.to_string()).unwrap_or_default(), c);
+        if let Ok(number) = text.parse() {
+            *value = Some(number);
+        }
+    }
+}
+
+// Remove the last digit of an optional number, clearing it when none are left
+fn pop_optional_digit<T: std::str::FromStr + ToString>(value: &mut Option<T>) {
+    let text = value.as_ref().map(|v| v.to_string()).unwrap_or_default();
+    *value = text[..text.len().saturating_sub(1)].parse().ok();
+}
@@ -1,24 +1,482 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use mysql::prelude::*;
-use mysql::{Pool, Row};
+use mysql::consts::ColumnType;
+use mysql::{Column, Conn, Opts, OptsBuilder, Pool, PooledConn, QueryResult, Row, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Instant;
 
+use crate::sql;
+
+pub const READ_ONLY_BLOCKED: &str = "read-only mode: statement blocked";
+
+#[derive(Clone)]
 pub struct DatabaseManager {
-    pool: Pool,
+    // Shared with the background handles, so a pool rebuilt after the server
+    // went away is used everywhere
+    pool: Arc<Mutex<Pool>>,
+    // What the pool was built from, to rebuild it
+    opts: Opts,
+    // The `init` statements the connection was opened with, before the query timeout
+    base_init: Vec<String>,
+    // VERSION() of the server, which decides how the query timeout is set
+    server_version: String,
+    // Server id of the connection most recently taken from the pool, so a
+    // query running on another thread can be killed
+    active_connection: Arc<AtomicU32>,
+    // Limit for statements, set by the pool's init statements; 0 means the server's own
+    query_timeout_secs: u64,
+    // Rows kept from one result set regardless of the row limit; 0 keeps all
+    max_result_rows: usize,
+    // Rows read so far by the running statement, for the progress line
+    rows_fetched: Arc<AtomicU64>,
+    // Reject anything that could write before it reaches the server
+    read_only: bool,
+    // Connection pinned by BEGIN until COMMIT/ROLLBACK; while it is set every
+    // statement runs on it instead of a fresh pooled connection
+    transaction: Arc<Mutex<Option<PooledConn>>>,
+}
+
+// The connection a statement runs on: the open transaction's, or one from the pool
+enum SessionConn<'a> {
+    Pooled(PooledConn),
+    Pinned(MutexGuard<'a, Option<PooledConn>>),
+}
+
+impl Deref for SessionConn<'_> {
+    type Target = Conn;
+
+    fn deref(&self) -> &Conn {
+        match self {
+            SessionConn::Pooled(conn) => conn,
+            SessionConn::Pinned(guard) => guard.as_ref().expect("pinned only while a transaction is open"),
+        }
+    }
+}
+
+impl DerefMut for SessionConn<'_> {
+    fn deref_mut(&mut self) -> &mut Conn {
+        match self {
+            SessionConn::Pooled(conn) => conn.as_mut(),
+            SessionConn::Pinned(guard) => guard.as_mut().expect("pinned only while a transaction is open").as_mut(),
+        }
+    }
+}
+
+// ER_QUERY_TIMEOUT (MySQL) and ER_STATEMENT_TIMEOUT (MariaDB)
+const QUERY_TIMEOUT_ERRORS: [u16; 2] = [3024, 1969];
+
+// CR_SERVER_GONE_ERROR and CR_SERVER_LOST
+const CONNECTION_LOST_ERRORS: [u16; 2] = [2006, 2013];
+
+// ER_NO_SUCH_THREAD and ER_KILL_DENIED_ERROR
+const NO_SUCH_THREAD: u16 = 1094;
+const KILL_DENIED: u16 = 1095;
+
+// One row of SHOW FULL PROCESSLIST
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    pub id: u64,
+    pub user: String,
+    pub host: String,
+    pub db: Option<String>,
+    pub command: String,
+    pub time: u64,
+    pub state: Option<String>,
+    pub info: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplicationStatus {
+    pub command: String,
+    pub source_host: String,
+    pub source_port: Option<u16>,
+    pub io_running: String,
+    pub sql_running: String,
+    pub seconds_behind: Option<u64>,
+    pub last_io_error: String,
+    pub last_sql_error: String,
+    pub source_log_file: String,
+    pub read_source_log_pos: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DumpFormat {
+    Sql,
+    Csv,
+}
+
+#[derive(Debug, Clone)]
+pub struct DumpOptions {
+    pub format: DumpFormat,
+    pub include_ddl: bool,
+    pub batch_size: usize,
+}
+
+// One result row; None is SQL NULL, kept apart from the string "NULL"
+pub type DataRow = Vec<Option<String>>;
+
+// Raw bytes of the binary cells of a page of table data, by (row, column)
+pub type BinaryCells = HashMap<(usize, usize), Vec<u8>>;
+
+// Column labels ("name (type)"), their keys, the rows and the binary cells of a page
+pub type TableContents = (Vec<String>, Vec<ColumnKey>, Vec<DataRow>, BinaryCells);
+
+const HEX_PREVIEW_BYTES: usize = 32;
+
+// Column names, whether each column is numeric, the rows and a status message
+pub type QueryOutput = (Vec<String>, Vec<bool>, Vec<DataRow>, String);
+
+// One statement of a script run by execute_script
+pub struct ScriptStep {
+    pub sql: String,
+    pub result: Result<QueryOutput>,
+    pub elapsed_ms: u64,
+}
+
+impl ScriptStep {
+    // Statements other than SELECTs report server errors in the message
+    pub fn failed(&self) -> bool {
+        match &self.result {
+            Ok((_, _, _, message)) => message.starts_with("Error"),
+            Err(_) => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExplainComparison {
+    pub plan_columns: Vec<String>,
+    pub plan_numeric_columns: Vec<bool>,
+    pub plan_rows: Vec<DataRow>,
+    pub primary_table: String,
+    pub estimated_rows: f64,
+    pub actual_rows: u64,
+}
+
+impl ExplainComparison {
+    // Estimated over actual; None when the actual count is zero
+    pub fn ratio(&self) -> Option<f64> {
+        if self.actual_rows == 0 {
+            None
+        } else {
+            Some(self.estimated_rows / self.actual_rows as f64)
+        }
+    }
+}
+
+// One row of DESCRIBE output
+#[derive(Debug, Clone)]
+pub struct ColumnDetail {
+    pub name: String,
+    pub column_type: String,
+    pub nullable: bool,
+    // PRI, UNI, MUL or empty
+    pub key: String,
+    pub default: Option<String>,
+    pub extra: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct IndexInfo {
+    pub name: String,
+    pub unique: bool,
+    // BTREE, FULLTEXT, SPATIAL, HASH
+    pub index_type: String,
+    // In index order; functional key parts show their expression
+    pub columns: Vec<String>,
+}
+
+// How a column takes part in the table's keys, from the Key field of DESCRIBE:
+// PRI columns belong to the primary key, UNI columns start a unique index
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnKey {
+    Primary,
+    Unique,
+    None,
+}
+
+impl ColumnKey {
+    fn from_describe(key: &str) -> Self {
+        match key {
+            "PRI" => ColumnKey::Primary,
+            "UNI" => ColumnKey::Unique,
+            _ => ColumnKey::None,
+        }
+    }
+    
+    // Prefix for the column's name in the data view header
+    pub fn marker(self) -> &'static str {
+        match self {
+            ColumnKey::Primary => "🔑 ",
+            ColumnKey::Unique => "◆ ",
+            ColumnKey::None => "",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ForeignKey {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub referenced_database: String,
+    pub referenced_table: String,
+    pub referenced_columns: Vec<String>,
+}
+
+// Snapshot of the server and of the session a statement runs on
+#[derive(Debug, Clone)]
+pub struct ServerInfo {
+    pub version: String,
+    pub current_user: String,
+    pub connection_id: u64,
+    pub character_set: String,
+    pub collation: String,
+    // Session time zone; "SYSTEM" is resolved to the server's system zone
+    pub time_zone: String,
+    pub uptime_secs: u64,
+}
+
+impl ServerInfo {
+    // Uptime as "3d 4h 12m"; under a minute as seconds
+    pub fn uptime(&self) -> String {
+        let secs = self.uptime_secs;
+        let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+        match (days, hours, minutes) {
+            (0, 0, 0) => format!("{}s", secs),
+            (0, 0, _) => format!("{}m", minutes),
+            (0, _, _) => format!("{}h {}m", hours, minutes),
+            _ => format!("{}d {}h {}m", days, hours, minutes),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BinlogStatus {
+    pub command: String,
+    pub file: String,
+    pub position: u64,
+    pub do_db: String,
+    pub ignore_db: String,
+    pub gtid_set: String,
+}
+
+impl BinlogStatus {
+    // Single-line form for pasting into backup or replication commands
+    pub fn summary(&self) -> String {
+        if self.gtid_set.is_empty() {
+            format!("{}:{}", self.file, self.position)
+        } else {
+            format!("{}:{} GTID {}", self.file, self.position, self.gtid_set)
+        }
+    }
+}
+
+impl ReplicationStatus {
+    pub fn is_healthy(&self) -> bool {
+        self.io_running == "Yes" && self.sql_running == "Yes"
+    }
 }
 
 impl DatabaseManager {
-    pub fn new(pool: Pool) -> Result<Self> {
-        // Test connection and set charset
-        {
-            let mut conn = pool.get_conn()?;
-            conn.query_drop("SET NAMES utf8mb4 COLLATE utf8mb4_unicode_ci")?;
+    // The charset comes from the `init` statements of `opts`, which the pool runs on
+    // every connection it opens or resets
+    pub fn new(opts: Opts) -> Result<Self> {
+        let pool = Pool::new(opts.clone()).context("Failed to create MySQL connection pool")?;
+        
+        // Test the connection
+        let mut conn = pool.get_conn().context("Failed to establish MySQL connection")?;
+        let server_version: Option<String> = conn.query_first("SELECT VERSION()")?;
+        drop(conn);
+        Ok(DatabaseManager {
+            pool: Arc::new(Mutex::new(pool)),
+            base_init: opts.get_init(),
+            opts,
+            server_version: server_version.unwrap_or_default(),
+            active_connection: Arc::new(AtomicU32::new(0)),
+            query_timeout_secs: 0,
+            max_result_rows: 0,
+            rows_fetched: Arc::new(AtomicU64::new(0)),
+            read_only: false,
+            transaction: Arc::new(Mutex::new(None)),
+        })
+    }
+    
+    // The timeout goes into the init statements, which the pool runs on every connection
+    // it opens and again after each reset, so statements don't each send it. Changing it
+    // rebuilds the pool; an open transaction's connection gets it directly.
+    pub fn set_query_timeout(&mut self, secs: u64) -> Result<()> {
+        if secs == self.query_timeout_secs {
+            return Ok(());
+        }
+        let statement = query_timeout_statement(&self.server_version, secs);
+        let mut init = self.base_init.clone();
+        init.extend(statement.clone());
+        let opts: Opts = OptsBuilder::from_opts(self.opts.clone()).init(init).into();
+        let pool = Pool::new(opts.clone()).context("Failed to apply the query timeout")?;
+        if let (Some(conn), Some(statement)) = (self.pinned().as_mut(), statement) {
+            conn.query_drop(statement)?;
+        }
+        *self.pool.lock().unwrap_or_else(PoisonError::into_inner) = pool;
+        self.opts = opts;
+        self.query_timeout_secs = secs;
+        Ok(())
+    }
+    
+    pub fn set_max_result_rows(&mut self, rows: usize) {
+        self.max_result_rows = rows;
+    }
+    
+    pub fn rows_fetched(&self) -> u64 {
+        self.rows_fetched.load(Ordering::Relaxed)
+    }
+    
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+    
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+    
+    fn check_writable(&self) -> Result<()> {
+        if self.read_only {
+            Err(anyhow!(READ_ONLY_BLOCKED))
+        } else {
+            Ok(())
+        }
+    }
+    
+    // A handle for a background thread, tracking its own active connection
+    pub fn background(&self) -> Self {
+        DatabaseManager {
+            pool: Arc::clone(&self.pool),
+            opts: self.opts.clone(),
+            base_init: self.base_init.clone(),
+            server_version: self.server_version.clone(),
+            active_connection: Arc::new(AtomicU32::new(0)),
+            query_timeout_secs: self.query_timeout_secs,
+            max_result_rows: self.max_result_rows,
+            rows_fetched: Arc::new(AtomicU64::new(0)),
+            read_only: self.read_only,
+            transaction: Arc::clone(&self.transaction),
+        }
+    }
+    
+    fn conn(&self) -> Result<SessionConn<'_>> {
+        let pinned = self.pinned();
+        let conn = if pinned.is_some() {
+            SessionConn::Pinned(pinned)
+        } else {
+            SessionConn::Pooled(self.pooled_conn()?)
+        };
+        self.active_connection.store(conn.connection_id(), Ordering::SeqCst);
+        Ok(conn)
+    }
+    
+    // A connection from the pool. The pool already replaces dead idle connections;
+    // when it can't connect at all because the server went away (restart, network
+    // drop), the pool is rebuilt from scratch and asked once more.
+    fn pooled_conn(&self) -> Result<PooledConn> {
+        let pool = self.pool.lock().unwrap_or_else(PoisonError::into_inner).clone();
+        match pool.get_conn() {
+            Ok(conn) => Ok(conn),
+            Err(e) if is_connection_lost(&e) => {
+                let pool = Pool::new(self.opts.clone()).context("Failed to reconnect to MySQL")?;
+                let conn = pool.get_conn().context("Failed to reconnect to MySQL")?;
+                *self.pool.lock().unwrap_or_else(PoisonError::into_inner) = pool;
+                Ok(conn)
+            },
+            Err(e) => Err(e.into()),
+        }
+    }
+    
+    fn pinned(&self) -> MutexGuard<'_, Option<PooledConn>> {
+        self.transaction.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    
+    // Pin a connection and start a transaction on it
+    pub fn begin_transaction(&self) -> Result<()> {
+        let mut pinned = self.pinned();
+        if pinned.is_some() {
+            return Err(anyhow!("a transaction is already open"));
+        }
+        let mut conn = self.pooled_conn()?;
+        conn.query_drop("START TRANSACTION")?;
+        *pinned = Some(conn);
+        Ok(())
+    }
+    
+    // COMMIT or ROLLBACK and release the pinned connection. It is released even
+    // when the statement fails; the pool resets it, which rolls back what is left.
+    pub fn end_transaction(&self, commit: bool) -> Result<()> {
+        let mut conn = self.pinned().take().ok_or_else(|| anyhow!("no transaction is open"))?;
+        conn.query_drop(if commit { "COMMIT" } else { "ROLLBACK" })?;
+        Ok(())
+    }
+    
+    // Kill the connection of a running query from a separate connection.
+    // The killed connection is dropped rather than returned to the pool.
+    pub fn kill_active_connection(&self) -> Result<()> {
+        let id = self.active_connection.load(Ordering::SeqCst);
+        if id != 0 {
+            self.pooled_conn()?.query_drop(format!("KILL {}", id))?;
+        }
+        Ok(())
+    }
+    
+    // Without the PROCESS privilege the server only lists the user's own threads
+    pub fn get_process_list(&self) -> Result<Vec<ProcessInfo>> {
+        let mut conn = self.conn()?;
+        let rows: Vec<Row> = conn.query("SHOW FULL PROCESSLIST")?;
+        
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let text = |name: &str| -> Option<String> {
+                    row.get_opt::<Option<String>, &str>(name).and_then(|v| v.ok()).flatten()
+                };
+                let number = |name: &str| -> u64 {
+                    row.get_opt::<u64, &str>(name).and_then(|v| v.ok()).unwrap_or(0)
+                };
+                ProcessInfo {
+                    id: number("Id"),
+                    user: text("User").unwrap_or_default(),
+                    host: text("Host").unwrap_or_default(),
+                    db: text("db"),
+                    command: text("Command").unwrap_or_default(),
+                    time: number("Time"),
+                    state: text("State").filter(|state| !state.is_empty()),
+                    info: text("Info"),
+                }
+            })
+            .collect())
+    }
+    
+    // KILL QUERY stops the statement and keeps the session; KILL closes the
+    // whole connection. Killing another user's thread needs PROCESS plus SUPER
+    // or CONNECTION_ADMIN, and the server's refusal is reworded to say so.
+    pub fn kill_query(&self, id: u64, whole_connection: bool) -> Result<()> {
+        self.check_writable()?;
+        let statement = if whole_connection { "KILL" } else { "KILL QUERY" };
+        match self.pooled_conn()?.query_drop(format!("{} {}", statement, id)) {
+            Ok(()) => Ok(()),
+            Err(mysql::Error::MySqlError(e)) if e.code == KILL_DENIED => Err(anyhow!(
+                "Not allowed to kill thread {} - it belongs to another user and killing it needs the SUPER or CONNECTION_ADMIN privilege",
+                id
+            )),
+            Err(mysql::Error::MySqlError(e)) if e.code == NO_SUCH_THREAD => {
+                Err(anyhow!("Thread {} no longer exists - it already finished (press r to reload)", id))
+            },
+            Err(e) => Err(e.into()),
         }
-        Ok(DatabaseManager { pool })
     }
     
     pub fn get_databases(&self) -> Result<Vec<String>> {
-        let mut conn = self.pool.get_conn()?;
-        conn.query_drop("SET NAMES utf8mb4 COLLATE utf8mb4_unicode_ci")?;
+        let mut conn = self.conn()?;
         
         let databases: Vec<String> = conn
             .query_map(
@@ -33,11 +491,10 @@ impl DatabaseManager {
     }
     
     pub fn get_tables(&self, database: &str) -> Result<Vec<String>> {
-        let mut conn = self.pool.get_conn()?;
-        conn.query_drop("SET NAMES utf8mb4 COLLATE utf8mb4_unicode_ci")?;
+        let mut conn = self.conn()?;
         
         // Switch to the specified database
-        conn.query_drop(format!("USE `{}`", database))?;
+        conn.query_drop(format!("USE {}", quote_identifier(database)))?;
         
         let tables: Vec<String> = conn
             .query_map(
@@ -48,138 +505,1083 @@ impl DatabaseManager {
         Ok(tables)
     }
     
-    pub fn get_table_data(&self, database: &str, table: &str) -> Result<(Vec<String>, Vec<Vec<String>>)> {
-        let mut conn = self.pool.get_conn()?;
-        conn.query_drop("SET NAMES utf8mb4 COLLATE utf8mb4_unicode_ci")?;
+    // Name, row estimate and data length per table. information_schema can be slow on
+    // servers with many tables, so this is only used when the stats view is toggled on.
+    // TABLE_ROWS is NULL for views, so the estimate is optional.
+    pub fn get_tables_with_stats(&self, database: &str) -> Result<Vec<(String, Option<u64>, u64)>> {
+        let mut conn = self.conn()?;
+        let stats: Vec<(String, Option<u64>, Option<u64>)> = conn.exec(
+            "SELECT TABLE_NAME, TABLE_ROWS, DATA_LENGTH FROM information_schema.TABLES \
+             WHERE TABLE_SCHEMA = ? ORDER BY TABLE_NAME",
+            (database,),
+        )?;
+        Ok(stats
+            .into_iter()
+            .map(|(name, rows, data_length)| (name, rows, data_length.unwrap_or(0)))
+            .collect())
+    }
+    
+    pub fn count_rows(&self, database: &str, table: &str, filter: Option<&str>) -> Result<u64> {
+        let mut conn = self.conn()?;
+        let count: Option<u64> = conn.query_first(format!(
+            "SELECT COUNT(*) FROM {}.{}{}",
+            quote_identifier(database),
+            quote_identifier(table),
+            where_clause(filter)
+        ))?;
+        Ok(count.unwrap_or(0))
+    }
+    
+    pub fn get_table_data(
+        &self,
+        database: &str,
+        table: &str,
+        offset: usize,
+        limit: Option<usize>,
+        order_by: &[(String, bool)],
+        filter: Option<&str>,
+    ) -> Result<TableContents> {
+        let mut conn = self.conn()?;
         
         // Switch to the specified database
-        conn.query_drop(format!("USE `{}`", database))?;
+        conn.query_drop(format!("USE {}", quote_identifier(database)))?;
         
-        // Get column information
-        let columns: Vec<String> = conn
+        // Get column information and which columns are keys
+        let (columns, keys): (Vec<String>, Vec<ColumnKey>) = conn
             .query_map(
-                format!("DESCRIBE `{}`", table),
+                format!("DESCRIBE {}", quote_identifier(table)),
                 |row: Row| {
                     let field: String = row.get("Field").unwrap_or_default();
                     let type_info: String = row.get("Type").unwrap_or_default();
-                    format!("{} ({})", field, type_info)
+                    let key: String = row.get("Key").unwrap_or_default();
+                    (format!("{} ({})", field, type_info), ColumnKey::from_describe(&key))
                 },
-            )?;
+            )?
+            .into_iter()
+            .unzip();
         
-        // Get table data (limit to first 100 rows for performance)
-        let query = format!("SELECT * FROM `{}` LIMIT 100", table);
+        // Get one page of table data (limited to the current row limit for performance,
+        // or every row when there is no limit)
+        let order_terms: Vec<String> = order_by
+            .iter()
+            .map(|(column, desc)| format!("{} {}", quote_identifier(column), if *desc { "DESC" } else { "ASC" }))
+            .collect();
+        let order_clause = if order_terms.is_empty() {
+            String::new()
+        } else {
+            format!(" ORDER BY {}", order_terms.join(", "))
+        };
+        let limit_clause = match limit {
+            Some(limit) => format!(" LIMIT {} OFFSET {}", limit, offset),
+            None => String::new(),
+        };
+        let query = format!(
+            "SELECT * FROM {}{}{}{}",
+            quote_identifier(table),
+            where_clause(filter),
+            order_clause,
+            limit_clause
+        );
         let result = conn.query_iter(query)?;
         
         let mut rows = Vec::new();
+        let mut binary = BinaryCells::new();
         for row_result in result {
-            let row = row_result?;
-            let mut row_data = Vec::new();
-            
-            // Convert each column value to string, handling NULL values properly
-            for i in 0..row.len() {
-                let value = row.get_opt::<String, usize>(i);
-                let string_value = match value {
-                    Some(Ok(s)) => s,
-                    Some(Err(_)) => {
-                        // Try to get as bytes and convert to string for better encoding handling
-                        let bytes_value = row.get_opt::<Vec<u8>, usize>(i);
-                        match bytes_value {
-                            Some(Ok(bytes)) => {
-                                match String::from_utf8(bytes) {
-                                    Ok(utf8_string) => utf8_string,
-                                    Err(_) => "(binary data)".to_string(),
-                                }
-                            },
-                            _ => "NULL".to_string(),
-                        }
-                    },
-                    None => "NULL".to_string(),
-                };
-                
-                row_data.push(string_value);
-            }
-            
-            rows.push(row_data);
+            let (cells, raw) = row_to_cells(&row_result?);
+            binary.extend(raw.into_iter().map(|(column, bytes)| ((rows.len(), column), bytes)));
+            rows.push(cells);
         }
         
-        Ok((columns, rows))
+        Ok((columns, keys, rows, binary))
+    }
+    
+    pub fn execute_sql(&self, sql: &str, database: Option<&str>, max_rows: Option<usize>) -> Result<QueryOutput> {
+        if !sql::is_read_only_statement(sql) {
+            self.check_writable()?;
+        }
+        let mut conn = self.session_conn(database)?;
+        self.run_statement(&mut conn, sql, None, max_rows)
+    }
+    
+    // Run a statement with its ? placeholders bound to `params` (None is NULL)
+    // through a prepared statement, so the values never become SQL text
+    pub fn execute_sql_params(
+        &self,
+        sql: &str,
+        params: Vec<Option<String>>,
+        database: Option<&str>,
+        max_rows: Option<usize>,
+    ) -> Result<QueryOutput> {
+        if !sql::is_read_only_statement(sql) {
+            self.check_writable()?;
+        }
+        let params = params.into_iter().map(|param| param.map_or(Value::NULL, Value::from)).collect();
+        let mut conn = self.session_conn(database)?;
+        self.run_statement(&mut conn, sql, Some(params), max_rows)
+    }
+    
+    // Run the statements of a script in order on one connection, so variables,
+    // USE and temporary tables carry over. With `stop_on_error` the statements
+    // after the first failure are not run (and not returned).
+    pub fn execute_script(
+        &self,
+        statements: Vec<String>,
+        database: Option<&str>,
+        max_rows: Option<usize>,
+        stop_on_error: bool,
+    ) -> Result<Vec<ScriptStep>> {
+        let mut conn = self.session_conn(database)?;
+        let mut steps = Vec::new();
+        for sql in statements {
+            let start = Instant::now();
+            let result = if sql::is_read_only_statement(&sql) {
+                self.run_statement(&mut conn, &sql, None, max_rows)
+            } else {
+                self.check_writable().and_then(|_| self.run_statement(&mut conn, &sql, None, max_rows))
+            };
+            let step = ScriptStep { sql, result, elapsed_ms: start.elapsed().as_millis() as u64 };
+            let failed = step.failed();
+            steps.push(step);
+            if failed && stop_on_error {
+                break;
+            }
+        }
+        Ok(steps)
     }
     
-    pub fn execute_sql(&self, sql: &str, database: Option<&str>) -> Result<(Vec<String>, Vec<Vec<String>>, String)> {
-        let mut conn = self.pool.get_conn()?;
-        conn.query_drop("SET NAMES utf8mb4 COLLATE utf8mb4_unicode_ci")?;
+    // A connection with the default database selected
+    fn session_conn(&self, database: Option<&str>) -> Result<SessionConn<'_>> {
+        let mut conn = self.conn()?;
         
         // Switch to database if specified
         if let Some(db) = database {
-            conn.query_drop(format!("USE `{}`", db))?;
+            conn.query_drop(format!("USE {}", quote_identifier(db)))?;
         }
-        
+        Ok(conn)
+    }
+    
+    // `params` binds the ? placeholders through a prepared statement; without
+    // them the statement is sent as text
+    fn run_statement(&self, conn: &mut Conn, sql: &str, params: Option<Vec<Value>>, max_rows: Option<usize>) -> Result<QueryOutput> {
         // Determine if this is a SELECT query or other type
         let sql_trimmed = sql.trim().to_uppercase();
         
-        if sql_trimmed.starts_with("SELECT") || sql_trimmed.starts_with("SHOW") || sql_trimmed.starts_with("DESCRIBE") || sql_trimmed.starts_with("EXPLAIN") {
+        if ["SELECT", "SHOW", "DESCRIBE", "EXPLAIN", "ANALYZE"].iter().any(|keyword| sql_trimmed.starts_with(keyword)) {
             // Execute SELECT-like query
-            let result = conn.query_iter(sql)?;
-            let mut columns = Vec::new();
-            let mut rows = Vec::new();
-            let mut first_row = true;
+            // Rows are read one at a time; the buffer stops at the row limit or
+            // max_result_rows, whichever is lower
+            let cap = (self.max_result_rows > 0).then_some(self.max_result_rows);
+            let capped = cap.is_some_and(|cap| max_rows.is_none_or(|max| cap < max));
+            let max_rows = if capped { cap } else { max_rows };
+            self.rows_fetched.store(0, Ordering::Relaxed);
             
-            for row_result in result {
-                let row = row_result?;
-                
-                // Get column names from the first row
-                if first_row {
-                    for i in 0..row.len() {
-                        if let Some(column_name) = row.columns().get(i) {
-                            columns.push(column_name.name_str().to_string());
-                        } else {
-                            columns.push(format!("Column_{}", i));
-                        }
-                    }
-                    first_row = false;
-                }
-                
-                let mut row_data = Vec::new();
-                for i in 0..row.len() {
-                    let value = row.get_opt::<String, usize>(i);
-                    let string_value = match value {
-                        Some(Ok(s)) => s,
-                        Some(Err(_)) => {
-                            let bytes_value = row.get_opt::<Vec<u8>, usize>(i);
-                            match bytes_value {
-                                Some(Ok(bytes)) => {
-                                    match String::from_utf8(bytes) {
-                                        Ok(utf8_string) => utf8_string,
-                                        Err(_) => "(binary data)".to_string(),
-                                    }
-                                },
-                                _ => "NULL".to_string(),
-                            }
-                        },
-                        None => "NULL".to_string(),
-                    };
-                    
-                    row_data.push(string_value);
-                }
-                rows.push(row_data);
+            match params {
+                Some(params) => {
+                    let result = conn.exec_iter(sql, params).map_err(|e| self.query_error(e))?;
+                    self.collect_rows(result, max_rows, capped)
+                },
+                None => {
+                    let result = conn.query_iter(sql).map_err(|e| self.query_error(e))?;
+                    self.collect_rows(result, max_rows, capped)
+                },
             }
-            
-            let message = format!("Query executed successfully. {} rows returned.", rows.len());
-            Ok((columns, rows, message))
         } else {
             // Execute non-SELECT query
-            let result = conn.query_drop(sql);
+            let result = match params {
+                Some(params) => conn.exec_drop(sql, params),
+                None => conn.query_drop(sql),
+            };
             match result {
                 Ok(()) => {
                     let affected_rows = conn.affected_rows();
-                    let message = format!("Query executed successfully. {} rows affected.", affected_rows);
-                    Ok((Vec::new(), Vec::new(), message))
+                    let last_insert_id = conn.last_insert_id();
+                    let mut message = format!("Query executed successfully. {} rows affected", affected_rows);
+                    if last_insert_id > 0 {
+                        message.push_str(&format!(", last insert id {}", last_insert_id));
+                    }
+                    
+                    // Truncations and implicit conversions only show up as warnings;
+                    // they follow the summary line, one per line
+                    let warnings: Vec<(String, u32, String)> = if conn.warnings() > 0 {
+                        conn.query("SHOW WARNINGS").unwrap_or_default()
+                    } else {
+                        Vec::new()
+                    };
+                    match warnings.len() {
+                        0 => message.push('.'),
+                        1 => message.push_str(", 1 warning."),
+                        count => message.push_str(&format!(", {} warnings.", count)),
+                    }
+                    for (level, code, text) in warnings {
+                        message.push_str(&format!("\n{} {}: {}", level, code, text));
+                    }
+                    Ok((Vec::new(), Vec::new(), Vec::new(), message))
                 },
+                // A timeout is a failure in history, like a timed-out SELECT
+                Err(e) if is_query_timeout(&e) => Err(self.query_error(e)),
                 Err(e) => {
                     let message = format!("Error: {}", e);
-                    Ok((Vec::new(), Vec::new(), message))
+                    Ok((Vec::new(), Vec::new(), Vec::new(), message))
                 }
             }
         }
     }
+    
+    // Read a result set row by row until `max_rows`; `capped` says the limit
+    // is max_result_rows rather than the row limit
+    fn collect_rows<P: Protocol>(&self, result: QueryResult<'_, '_, '_, P>, max_rows: Option<usize>, capped: bool) -> Result<QueryOutput> {
+        let mut columns = Vec::new();
+        let mut numeric_columns = Vec::new();
+        let mut rows = Vec::new();
+        let mut truncated = false;
+        
+        // Column names come from the result set itself, so a SELECT without rows keeps its header
+        for column in result.columns().as_ref() {
+            columns.push(column.name_str().to_string());
+            numeric_columns.push(is_numeric_column(column));
+        }
+        
+        for row_result in result {
+            let row = row_result.map_err(|e| self.query_error(e))?;
+            
+            // Stop collecting once the row limit is reached
+            if max_rows.is_some_and(|max| rows.len() >= max) {
+                truncated = true;
+                break;
+            }
+            self.rows_fetched.fetch_add(1, Ordering::Relaxed);
+            
+            rows.push(row_to_strings(&row));
+        }
+        
+        let message = if truncated && capped {
+            format!("Query executed successfully. Results truncated at {} rows (max_result_rows).", rows.len())
+        } else if truncated {
+            format!("Query executed successfully. Showing first {} rows (row limit reached).", rows.len())
+        } else if rows.is_empty() {
+            "Query returned 0 rows.".to_string()
+        } else {
+            format!("Query executed successfully. {} rows returned.", rows.len())
+        };
+        Ok((columns, numeric_columns, rows, message))
+    }
+    
+    // MySQL limits SELECTs with MAX_EXECUTION_TIME (milliseconds); MariaDB has
+    // max_statement_time (seconds) instead. Pooled connections keep session
+    // variables, so 0 is set explicitly to clear a previous limit.
+    // A server-side timeout gets its own message; other errors pass through
+    fn query_error(&self, error: mysql::Error) -> anyhow::Error {
+        if is_query_timeout(&error) {
+            anyhow::anyhow!(
+                "Query timed out after {}s (query_timeout_secs in user_config.json, 0 disables it)",
+                self.query_timeout_secs
+            )
+        } else {
+            error.into()
+        }
+    }
+    
+    pub fn explain_with_actual(&self, sql: &str, database: Option<&str>) -> Result<ExplainComparison> {
+        let sql = sql.trim().trim_end_matches(';');
+        let (plan_columns, plan_numeric_columns, plan_rows, _) = self.execute_sql(&format!("EXPLAIN {}", sql), database, None)?;
+        
+        let column_index = |name: &str| plan_columns.iter().position(|c| c.eq_ignore_ascii_case(name));
+        let first_row = plan_rows.first().ok_or_else(|| anyhow::anyhow!("EXPLAIN returned no plan"))?;
+        let value = |name: &str| column_index(name).and_then(|i| first_row.get(i)).cloned().flatten().unwrap_or_default();
+        
+        // The first plan row is the table the optimizer reads first
+        let primary_table = value("table");
+        let examined: f64 = value("rows").parse().unwrap_or(0.0);
+        let filtered: f64 = value("filtered").parse().unwrap_or(100.0);
+        
+        let mut conn = self.conn()?;
+        if let Some(db) = database {
+            conn.query_drop(format!("USE {}", quote_identifier(db)))?;
+        }
+        
+        // For SELECTs compare against the rows the query really returns,
+        // otherwise fall back to the size of the primary table
+        let (estimated_rows, count_query) = if sql.to_uppercase().starts_with("SELECT") {
+            (examined * filtered / 100.0, format!("SELECT COUNT(*) FROM ({}) AS rmsql_actual", sql))
+        } else {
+            (examined, format!("SELECT COUNT(*) FROM {}", quote_identifier(&primary_table)))
+        };
+        let actual_rows: Option<u64> = conn.query_first(count_query)?;
+        
+        Ok(ExplainComparison {
+            plan_columns,
+            plan_numeric_columns,
+            plan_rows,
+            primary_table,
+            estimated_rows,
+            actual_rows: actual_rows.unwrap_or(0),
+        })
+    }
+    
+    // The plan of a statement without running it, or with `analyze` the plan with the
+    // real row counts and timings from running it
+    pub fn explain_sql(&self, sql: &str, database: Option<&str>, analyze: bool) -> Result<QueryOutput> {
+        let sql = sql.trim().trim_end_matches(';');
+        let prefix = if analyze {
+            let version = self.get_server_version()?;
+            explain_analyze_command(&version)
+                .ok_or_else(|| anyhow!("EXPLAIN ANALYZE needs MySQL 8.0.18 or MariaDB 10.1 (server is {})", version))?
+        } else {
+            "EXPLAIN"
+        };
+        self.execute_sql(&format!("{} {}", prefix, sql), database, None)
+    }
+    
+    pub fn get_server_version(&self) -> Result<String> {
+        let mut conn = self.conn()?;
+        let version: Option<String> = conn.query_first("SELECT VERSION()")?;
+        Ok(version.unwrap_or_default())
+    }
+    
+    pub fn get_server_info(&self) -> Result<ServerInfo> {
+        let mut conn = self.conn()?;
+        type SessionRow = (Option<String>, Option<String>, u64, Option<String>, Option<String>, Option<String>, Option<String>);
+        let row: Option<SessionRow> = conn.query_first(
+            "SELECT VERSION(), CURRENT_USER(), CONNECTION_ID(), @@character_set_connection, \
+             @@collation_connection, @@session.time_zone, @@system_time_zone",
+        )?;
+        let (version, current_user, connection_id, character_set, collation, time_zone, system_time_zone) =
+            row.ok_or_else(|| anyhow!("The server returned no session information"))?;
+        
+        let uptime: Option<(String, String)> = conn.query_first("SHOW GLOBAL STATUS LIKE 'Uptime'")?;
+        let time_zone = match time_zone {
+            Some(zone) if zone.eq_ignore_ascii_case("SYSTEM") => format!("SYSTEM ({})", system_time_zone.unwrap_or_default()),
+            zone => zone.unwrap_or_default(),
+        };
+        
+        Ok(ServerInfo {
+            version: version.unwrap_or_default(),
+            current_user: current_user.unwrap_or_default(),
+            connection_id,
+            character_set: character_set.unwrap_or_default(),
+            collation: collation.unwrap_or_default(),
+            time_zone,
+            uptime_secs: uptime.and_then(|(_, value)| value.parse().ok()).unwrap_or(0),
+        })
+    }
+    
+    pub fn get_binlog_status(&self) -> Result<Option<BinlogStatus>> {
+        let version = self.get_server_version()?;
+        let command = binlog_status_command(&version);
+        
+        let mut conn = self.conn()?;
+        let row: Option<Row> = conn.query_first(command)?;
+        
+        // No row means binary logging is disabled
+        let row = match row {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+        
+        let text = |name: &str| -> String {
+            row.get_opt::<Option<String>, &str>(name)
+                .and_then(|v| v.ok())
+                .flatten()
+                .unwrap_or_default()
+        };
+        
+        // MariaDB doesn't report GTIDs in the status row
+        let mut gtid_set = text("Executed_Gtid_Set");
+        if gtid_set.is_empty() && version.contains("MariaDB") {
+            let pos: Option<Option<String>> = conn.query_first("SELECT @@gtid_binlog_pos")?;
+            gtid_set = pos.flatten().unwrap_or_default();
+        }
+        
+        Ok(Some(BinlogStatus {
+            command: command.to_string(),
+            file: text("File"),
+            position: row.get_opt::<u64, &str>("Position").and_then(|v| v.ok()).unwrap_or(0),
+            do_db: text("Binlog_Do_DB"),
+            ignore_db: text("Binlog_Ignore_DB"),
+            gtid_set: gtid_set.replace('\n', ""),
+        }))
+    }
+    
+    pub fn get_replication_status(&self) -> Result<Option<ReplicationStatus>> {
+        let version = self.get_server_version()?;
+        let command = if supports_replica_syntax(&version) {
+            "SHOW REPLICA STATUS"
+        } else {
+            "SHOW SLAVE STATUS"
+        };
+        
+        let mut conn = self.conn()?;
+        let row: Option<Row> = conn.query_first(command)?;
+        
+        // No row means this server is not configured as a replica
+        let row = match row {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+        
+        // Newer servers renamed Slave/Master columns to Replica/Source, MariaDB kept the old names
+        let text = |names: &[&str]| -> String {
+            names.iter()
+                .find_map(|name| row.get_opt::<Option<String>, &str>(name).and_then(|v| v.ok()).flatten())
+                .unwrap_or_default()
+        };
+        let number = |names: &[&str]| -> Option<u64> {
+            names.iter()
+                .find_map(|name| row.get_opt::<Option<u64>, &str>(name).and_then(|v| v.ok()).flatten())
+        };
+        
+        Ok(Some(ReplicationStatus {
+            command: command.to_string(),
+            source_host: text(&["Source_Host", "Master_Host"]),
+            source_port: number(&["Source_Port", "Master_Port"]).map(|port| port as u16),
+            io_running: text(&["Replica_IO_Running", "Slave_IO_Running"]),
+            sql_running: text(&["Replica_SQL_Running", "Slave_SQL_Running"]),
+            seconds_behind: number(&["Seconds_Behind_Source", "Seconds_Behind_Master"]),
+            last_io_error: text(&["Last_IO_Error"]),
+            last_sql_error: text(&["Last_SQL_Error"]),
+            source_log_file: text(&["Source_Log_File", "Master_Log_File"]),
+            read_source_log_pos: number(&["Read_Source_Log_Pos", "Read_Master_Log_Pos"]),
+        }))
+    }
+    
+    pub fn get_columns(&self, database: &str, table: &str) -> Result<Vec<String>> {
+        let mut conn = self.conn()?;
+        let columns: Vec<String> = conn.exec(
+            "SELECT COLUMN_NAME FROM information_schema.COLUMNS \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? ORDER BY ORDINAL_POSITION",
+            (database, table),
+        )?;
+        Ok(columns)
+    }
+    
+    // ORDER BY terms for a page of table data: the chosen sort column, then with
+    // `by_key` the row key, which makes the order deterministic so pages don't
+    // overlap or skip rows (and breaks ties of the sort column)
+    pub fn table_order(&self, database: &str, table: &str, sort: Option<(String, bool)>, by_key: bool) -> Result<Vec<(String, bool)>> {
+        let mut order: Vec<(String, bool)> = sort.into_iter().collect();
+        if by_key {
+            for key in self.get_primary_key(database, table)?.unwrap_or_default() {
+                if !order.iter().any(|(column, _)| *column == key) {
+                    order.push((key, false));
+                }
+            }
+        }
+        Ok(order)
+    }
+    
+    // Columns that identify a single row: the primary key, or else the first unique
+    // key whose columns are all NOT NULL. None when the table has neither.
+    pub fn get_primary_key(&self, database: &str, table: &str) -> Result<Option<Vec<String>>> {
+        let mut conn = self.conn()?;
+        let key_columns: Vec<(String, String, String)> = conn.exec(
+            "SELECT s.INDEX_NAME, s.COLUMN_NAME, c.IS_NULLABLE \
+             FROM information_schema.STATISTICS s \
+             JOIN information_schema.COLUMNS c ON c.TABLE_SCHEMA = s.TABLE_SCHEMA \
+               AND c.TABLE_NAME = s.TABLE_NAME AND c.COLUMN_NAME = s.COLUMN_NAME \
+             WHERE s.TABLE_SCHEMA = ? AND s.TABLE_NAME = ? AND s.NON_UNIQUE = 0 \
+             ORDER BY s.INDEX_NAME = 'PRIMARY' DESC, s.INDEX_NAME, s.SEQ_IN_INDEX",
+            (database, table),
+        )?;
+        
+        let mut index_names: Vec<&String> = key_columns.iter().map(|(index, _, _)| index).collect();
+        index_names.dedup();
+        for index in index_names {
+            let columns: Vec<&(String, String, String)> = key_columns.iter().filter(|(name, _, _)| name == index).collect();
+            if columns.iter().all(|(_, _, nullable)| nullable == "NO") {
+                return Ok(Some(columns.iter().map(|(_, column, _)| column.clone()).collect()));
+            }
+        }
+        Ok(None)
+    }
+    
+    // Set one column of the row identified by its key. Values are sent as
+    // parameters; None writes SQL NULL. Returns the number of rows matched, so
+    // a row that already held the new value still counts.
+    pub fn update_cell(
+        &self,
+        database: &str,
+        table: &str,
+        column: &str,
+        value: Option<&str>,
+        key_columns: &[String],
+        key_values: &[String],
+    ) -> Result<u64> {
+        self.check_writable()?;
+        let mut conn = self.conn()?;
+        
+        let query = format!(
+            "UPDATE {}.{} SET {} = ? WHERE {} LIMIT 1",
+            quote_identifier(database),
+            quote_identifier(table),
+            quote_identifier(column),
+            key_condition(key_columns)
+        );
+        let mut params = vec![value.map_or(Value::NULL, Value::from)];
+        params.extend(key_values.iter().map(|v| Value::from(v.as_str())));
+        conn.exec_drop(query, params)?;
+        if conn.affected_rows() > 0 {
+            return Ok(conn.affected_rows());
+        }
+        
+        // The server only counts rows it changed, so check whether the row is
+        // still there before calling it gone
+        let query = format!(
+            "SELECT COUNT(*) FROM {}.{} WHERE {}",
+            quote_identifier(database),
+            quote_identifier(table),
+            key_condition(key_columns)
+        );
+        let params: Vec<Value> = key_values.iter().map(|v| Value::from(v.as_str())).collect();
+        let matched: Option<u64> = conn.exec_first(query, params)?;
+        Ok(matched.unwrap_or(0).min(1))
+    }
+    
+    // Delete the row identified by its key. Returns the number of rows deleted.
+    pub fn delete_row(&self, database: &str, table: &str, key_columns: &[String], key_values: &[String]) -> Result<u64> {
+        self.check_writable()?;
+        let mut conn = self.conn()?;
+        
+        let query = format!(
+            "DELETE FROM {}.{} WHERE {} LIMIT 1",
+            quote_identifier(database),
+            quote_identifier(table),
+            key_condition(key_columns)
+        );
+        let params: Vec<Value> = key_values.iter().map(|v| Value::from(v.as_str())).collect();
+        conn.exec_drop(query, params)?;
+        Ok(conn.affected_rows())
+    }
+    
+    // Fetch the current state of the rows identified by the given key values,
+    // used to show what an UPDATE/DELETE actually touched
+    pub fn fetch_rows_by_key(
+        &self,
+        database: &str,
+        table: &str,
+        key_columns: &[String],
+        keys: &[Vec<String>],
+    ) -> Result<(Vec<String>, Vec<DataRow>)> {
+        if key_columns.is_empty() || keys.is_empty() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+        
+        let mut conn = self.conn()?;
+        
+        let condition = vec![format!("({})", key_condition(key_columns)); keys.len()].join(" OR ");
+        let query = format!(
+            "SELECT * FROM {}.{} WHERE {}",
+            quote_identifier(database),
+            quote_identifier(table),
+            condition
+        );
+        let params: Vec<Value> = keys.iter().flatten().map(|v| Value::from(v.as_str())).collect();
+        
+        let result = conn.exec_iter(query, params)?;
+        let columns: Vec<String> = result
+            .columns()
+            .as_ref()
+            .iter()
+            .map(|column| column.name_str().to_string())
+            .collect();
+        
+        let mut rows = Vec::new();
+        for row_result in result {
+            let row = row_result?;
+            rows.push(row_to_strings(&row));
+        }
+        
+        Ok((columns, rows))
+    }
+    
+    pub fn get_create_table(&self, database: &str, table: &str) -> Result<String> {
+        let mut conn = self.conn()?;
+        
+        let row: Option<Row> = conn.query_first(format!(
+            "SHOW CREATE TABLE {}.{}",
+            quote_identifier(database),
+            quote_identifier(table)
+        ))?;
+        let ddl = row
+            .and_then(|row| row.get_opt::<String, usize>(1))
+            .and_then(|value| value.ok())
+            .unwrap_or_default();
+        Ok(ddl)
+    }
+    
+    pub fn get_column_details(&self, database: &str, table: &str) -> Result<Vec<ColumnDetail>> {
+        let mut conn = self.conn()?;
+        
+        let columns = conn.query_map(
+            format!("DESCRIBE {}.{}", quote_identifier(database), quote_identifier(table)),
+            |row: Row| {
+                let null: String = row.get("Null").unwrap_or_default();
+                ColumnDetail {
+                    name: row.get("Field").unwrap_or_default(),
+                    column_type: row.get("Type").unwrap_or_default(),
+                    nullable: null == "YES",
+                    key: row.get("Key").unwrap_or_default(),
+                    default: row.get::<Option<String>, _>("Default").flatten(),
+                    extra: row.get("Extra").unwrap_or_default(),
+                }
+            },
+        )?;
+        Ok(columns)
+    }
+    
+    pub fn get_indexes(&self, database: &str, table: &str) -> Result<Vec<IndexInfo>> {
+        let mut conn = self.conn()?;
+        
+        let parts: Vec<(String, bool, String, String)> = conn.query_map(
+            format!("SHOW INDEX FROM {}.{}", quote_identifier(database), quote_identifier(table)),
+            |row: Row| {
+                let non_unique: i64 = row.get("Non_unique").unwrap_or(1);
+                let column = row
+                    .get::<Option<String>, _>("Column_name")
+                    .flatten()
+                    .or_else(|| row.get::<Option<String>, _>("Expression").flatten())
+                    .unwrap_or_default();
+                (
+                    row.get("Key_name").unwrap_or_default(),
+                    non_unique == 0,
+                    row.get("Index_type").unwrap_or_default(),
+                    column,
+                )
+            },
+        )?;
+        
+        // SHOW INDEX lists the parts of each index together, in Seq_in_index order
+        let mut indexes: Vec<IndexInfo> = Vec::new();
+        for (name, unique, index_type, column) in parts {
+            match indexes.last_mut() {
+                Some(index) if index.name == name => index.columns.push(column),
+                _ => indexes.push(IndexInfo { name, unique, index_type, columns: vec![column] }),
+            }
+        }
+        Ok(indexes)
+    }
+    
+    pub fn get_foreign_keys(&self, database: &str, table: &str) -> Result<Vec<ForeignKey>> {
+        let mut conn = self.conn()?;
+        let parts: Vec<(String, String, String, String, String)> = conn.exec(
+            "SELECT CONSTRAINT_NAME, COLUMN_NAME, REFERENCED_TABLE_SCHEMA, REFERENCED_TABLE_NAME, REFERENCED_COLUMN_NAME \
+             FROM information_schema.KEY_COLUMN_USAGE \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? AND REFERENCED_TABLE_NAME IS NOT NULL \
+             ORDER BY CONSTRAINT_NAME, ORDINAL_POSITION",
+            (database, table),
+        )?;
+        
+        let mut foreign_keys: Vec<ForeignKey> = Vec::new();
+        for (name, column, referenced_database, referenced_table, referenced_column) in parts {
+            match foreign_keys.last_mut() {
+                Some(key) if key.name == name => {
+                    key.columns.push(column);
+                    key.referenced_columns.push(referenced_column);
+                },
+                _ => foreign_keys.push(ForeignKey {
+                    name,
+                    columns: vec![column],
+                    referenced_database,
+                    referenced_table,
+                    referenced_columns: vec![referenced_column],
+                }),
+            }
+        }
+        Ok(foreign_keys)
+    }
+    
+    pub fn dump_table_data<W: Write>(
+        &self,
+        database: &str,
+        table: &str,
+        writer: &mut W,
+        options: &DumpOptions,
+        mut progress: impl FnMut(u64),
+    ) -> Result<u64> {
+        if options.format == DumpFormat::Sql && options.include_ddl {
+            let ddl = self.get_create_table(database, table)?;
+            writeln!(writer, "-- Dump of {}.{}", quote_identifier(database), quote_identifier(table))?;
+            writeln!(writer, "DROP TABLE IF EXISTS {};", quote_identifier(table))?;
+            writeln!(writer, "{};\n", ddl)?;
+        }
+        
+        let mut conn = self.conn()?;
+        // A dump takes as long as the table is big, so the query timeout doesn't apply
+        if self.query_timeout_secs > 0 {
+            if let Some(statement) = query_timeout_statement(&self.server_version, 0) {
+                conn.query_drop(statement)?;
+            }
+        }
+        
+        // Rows are streamed from the server and written out in batches,
+        // so the whole table never has to fit in memory
+        let result = conn.query_iter(format!("SELECT * FROM {}.{}", quote_identifier(database), quote_identifier(table)))?;
+        let columns: Vec<String> = result
+            .columns()
+            .as_ref()
+            .iter()
+            .map(|column| column.name_str().to_string())
+            .collect();
+        
+        if options.format == DumpFormat::Csv {
+            let header: Vec<String> = columns.iter().map(|c| csv_field(c)).collect();
+            writeln!(writer, "{}", header.join(","))?;
+        }
+        
+        let column_list = columns
+            .iter()
+            .map(|c| quote_identifier(c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        
+        let batch_size = options.batch_size.max(1);
+        let mut batch: Vec<String> = Vec::with_capacity(batch_size);
+        let mut total = 0u64;
+        
+        for row_result in result {
+            let row = row_result?;
+            let values: Vec<Value> = (0..row.len())
+                .map(|i| row.as_ref(i).cloned().unwrap_or(Value::NULL))
+                .collect();
+            
+            let line = match options.format {
+                DumpFormat::Sql => {
+                    let literals: Vec<String> = values.iter().map(|v| v.as_sql(false)).collect();
+                    format!("({})", literals.join(", "))
+                },
+                DumpFormat::Csv => {
+                    let fields: Vec<String> = values.iter().map(csv_value).collect();
+                    fields.join(",")
+                },
+            };
+            batch.push(line);
+            total += 1;
+            
+            if batch.len() >= batch_size {
+                write_dump_batch(writer, options.format, table, &column_list, &batch)?;
+                batch.clear();
+                progress(total);
+            }
+        }
+        
+        if !batch.is_empty() {
+            write_dump_batch(writer, options.format, table, &column_list, &batch)?;
+            progress(total);
+        }
+        
+        writer.flush()?;
+        Ok(total)
+    }
+}
+
+// `a` = ? AND `b` = ? for a row key
+fn key_condition(key_columns: &[String]) -> String {
+    key_columns
+        .iter()
+        .map(|column| format!("{} = ?", quote_identifier(column)))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+fn is_query_timeout(error: &mysql::Error) -> bool {
+    matches!(error, mysql::Error::MySqlError(e) if QUERY_TIMEOUT_ERRORS.contains(&e.code))
+}
+
+// The server is unreachable or closed the connection, as opposed to rejecting a statement
+fn is_connection_lost(error: &mysql::Error) -> bool {
+    match error {
+        mysql::Error::IoError(_) => true,
+        mysql::Error::DriverError(e) => matches!(
+            e,
+            mysql::DriverError::ConnectTimeout | mysql::DriverError::CouldNotConnect(_) | mysql::DriverError::PacketOutOfSync
+        ),
+        mysql::Error::MySqlError(e) => CONNECTION_LOST_ERRORS.contains(&e.code),
+        _ => false,
+    }
+}
+
+// Whether an error from any DatabaseManager method means the connection is gone
+pub fn is_connection_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| cause.downcast_ref::<mysql::Error>().is_some_and(is_connection_lost))
+}
+
+// Code, SQLSTATE and message of a server error as the mysql crate formats it:
+// "MySqlError { ERROR 1062 (23000): Duplicate entry ... }"
+pub fn split_mysql_error(text: &str) -> Option<(&str, &str, &str)> {
+    let rest = &text[text.find("ERROR ")? + "ERROR ".len()..];
+    let (code, rest) = rest.split_once(" (")?;
+    let (state, message) = rest.split_once("): ")?;
+    if code.is_empty() || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let message = if text.starts_with("MySqlError {") {
+        message.strip_suffix(" }").unwrap_or(message)
+    } else {
+        message
+    };
+    Some((code, state, message))
+}
+
+// Backtick-quote an identifier so reserved words and odd names are safe in SQL
+pub fn quote_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+// Quoted and escaped string literal for building WHERE expressions
+pub fn quote_string(value: &str) -> String {
+    Value::from(value).as_sql(false)
+}
+
+// Whether a DESCRIBE type such as "int(10) unsigned" or "decimal(8,2)" holds numbers
+pub fn is_numeric_type(type_name: &str) -> bool {
+    let base = type_name
+        .split(|c: char| c == '(' || c.is_whitespace())
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    matches!(
+        base.as_str(),
+        "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint"
+            | "decimal" | "numeric" | "float" | "double" | "real"
+    )
+}
+
+// Whether a DESCRIBE type is MySQL's native JSON (MariaDB reports its JSON
+// alias as longtext, so those values are only recognized by their content)
+pub fn is_json_type(type_name: &str) -> bool {
+    type_name.trim().eq_ignore_ascii_case("json")
+}
+
+fn is_numeric_column(column: &Column) -> bool {
+    matches!(
+        column.column_type(),
+        ColumnType::MYSQL_TYPE_TINY
+            | ColumnType::MYSQL_TYPE_SHORT
+            | ColumnType::MYSQL_TYPE_INT24
+            | ColumnType::MYSQL_TYPE_LONG
+            | ColumnType::MYSQL_TYPE_LONGLONG
+            | ColumnType::MYSQL_TYPE_DECIMAL
+            | ColumnType::MYSQL_TYPE_NEWDECIMAL
+            | ColumnType::MYSQL_TYPE_FLOAT
+            | ColumnType::MYSQL_TYPE_DOUBLE
+    )
+}
+
+// The user's row filter is spliced in verbatim; it is their own SQL, like the editor
+fn where_clause(filter: Option<&str>) -> String {
+    match filter {
+        Some(filter) => format!(" WHERE {}", filter),
+        None => String::new(),
+    }
+}
+
+fn row_to_strings(row: &Row) -> DataRow {
+    row_to_cells(row).0
+}
+
+// The row as strings, plus the raw bytes of the values that aren't text by column
+// index. Those show as a hex preview (see hex_preview).
+fn row_to_cells(row: &Row) -> (DataRow, Vec<(usize, Vec<u8>)>) {
+    let mut binary = Vec::new();
+    let cells = (0..row.len())
+        .map(|i| {
+            // SQL NULL stays distinct from any string value
+            if let Some(Value::NULL) = row.as_ref(i) {
+                return None;
+            }
+            // Prepared statements return numbers and dates as typed values
+            let column_type = row.columns_ref().get(i).map(|column| column.column_type());
+            if let Some(text) = row.as_ref(i).and_then(|value| typed_value_text(value, column_type)) {
+                return Some(text);
+            }
+            match row.get_opt::<String, usize>(i) {
+                Some(Ok(s)) => Some(s),
+                Some(Err(_)) => {
+                    // Try to get as bytes and convert to string for better encoding handling
+                    match row.get_opt::<Vec<u8>, usize>(i) {
+                        Some(Ok(bytes)) => match decode_text(bytes) {
+                            Ok(text) => Some(text),
+                            Err(bytes) => {
+                                let preview = hex_preview(&bytes);
+                                binary.push((i, bytes));
+                                Some(preview)
+                            },
+                        },
+                        _ => None,
+                    }
+                },
+                None => None,
+            }
+        })
+        .collect();
+    (cells, binary)
+}
+
+// Text form of the non-text values of the binary protocol, as the text
+// protocol would have sent them
+fn typed_value_text(value: &Value, column_type: Option<ColumnType>) -> Option<String> {
+    match *value {
+        Value::Int(n) => Some(n.to_string()),
+        Value::UInt(n) => Some(n.to_string()),
+        Value::Float(n) => Some(n.to_string()),
+        Value::Double(n) => Some(n.to_string()),
+        Value::Date(year, month, day, hour, minute, second, micros) => {
+            let date = format!("{:04}-{:02}-{:02}", year, month, day);
+            Some(match (column_type, micros) {
+                (Some(ColumnType::MYSQL_TYPE_DATE), _) => date,
+                (_, 0) => format!("{} {:02}:{:02}:{:02}", date, hour, minute, second),
+                _ => format!("{} {:02}:{:02}:{:02}.{:06}", date, hour, minute, second, micros),
+            })
+        },
+        Value::Time(negative, days, hours, minutes, seconds, micros) => {
+            let sign = if negative { "-" } else { "" };
+            let hours = days * 24 + hours as u32;
+            Some(match micros {
+                0 => format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds),
+                _ => format!("{}{:02}:{:02}:{:02}.{:06}", sign, hours, minutes, seconds, micros),
+            })
+        },
+        Value::NULL | Value::Bytes(_) => None,
+    }
+}
+
+// UTF-8, or latin1 when every byte is a printable latin1 character (text stored
+// under the wrong charset); anything else is binary and is handed back as is
+fn decode_text(bytes: Vec<u8>) -> std::result::Result<String, Vec<u8>> {
+    let bytes = match String::from_utf8(bytes) {
+        Ok(text) => return Ok(text),
+        Err(e) => e.into_bytes(),
+    };
+    let printable = |b: u8| matches!(b, b'\t' | b'\n' | b'\r' | 0x20..=0x7e | 0xa0..=0xff);
+    if bytes.iter().all(|&b| printable(b)) {
+        Ok(bytes.iter().map(|&b| b as char).collect())
+    } else {
+        Err(bytes)
+    }
+}
+
+// `0x` and the first HEX_PREVIEW_BYTES bytes in hex, then the total length
+pub fn hex_preview(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().take(HEX_PREVIEW_BYTES).map(|b| format!("{:02X}", b)).collect();
+    let more = if bytes.len() > HEX_PREVIEW_BYTES { "…" } else { "" };
+    format!("0x{}{} ({} bytes)", hex, more, bytes.len())
+}
+
+fn write_dump_batch<W: Write>(writer: &mut W, format: DumpFormat, table: &str, column_list: &str, batch: &[String]) -> Result<()> {
+    match format {
+        DumpFormat::Sql => {
+            writeln!(writer, "INSERT INTO {} ({}) VALUES\n{};", quote_identifier(table), column_list, batch.join(",\n"))?;
+        },
+        DumpFormat::Csv => {
+            for line in batch {
+                writeln!(writer, "{}", line)?;
+            }
+        },
+    }
+    Ok(())
+}
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::NULL => "\\N".to_string(),
+        Value::Bytes(bytes) => csv_field(&String::from_utf8_lossy(bytes)),
+        // Non-text values come back as SQL literals; strip the quotes for CSV
+        other => csv_field(other.as_sql(true).trim_matches('\'')),
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') || field.contains('\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// SHOW BINARY LOG STATUS replaced SHOW MASTER STATUS in MySQL 8.2; MariaDB has SHOW BINLOG STATUS since 10.5.2
+fn binlog_status_command(version: &str) -> &'static str {
+    match parse_version(version) {
+        Some(v) if version.contains("MariaDB") && v >= (10, 5, 2) => "SHOW BINLOG STATUS",
+        Some(v) if !version.contains("MariaDB") && v >= (8, 2, 0) => "SHOW BINARY LOG STATUS",
+        _ => "SHOW MASTER STATUS",
+    }
+}
+
+// MySQL has EXPLAIN ANALYZE since 8.0.18; MariaDB's equivalent is the ANALYZE statement
+fn explain_analyze_command(version: &str) -> Option<&'static str> {
+    match parse_version(version) {
+        Some(v) if version.contains("MariaDB") && v >= (10, 1, 0) => Some("ANALYZE"),
+        Some(v) if !version.contains("MariaDB") && v >= (8, 0, 18) => Some("EXPLAIN ANALYZE"),
+        _ => None,
+    }
+}
+
+// SET for the query timeout on this server; None when it has none (MySQL before
+// 5.7.8, MariaDB before 10.1.1). MySQL's limit only covers SELECT.
+fn query_timeout_statement(version: &str, secs: u64) -> Option<String> {
+    let v = parse_version(version)?;
+    if version.contains("MariaDB") {
+        (v >= (10, 1, 1)).then(|| format!("SET SESSION max_statement_time = {}", secs))
+    } else {
+        (v >= (5, 7, 8)).then(|| format!("SET SESSION MAX_EXECUTION_TIME = {}", secs.saturating_mul(1000)))
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let numbers: Vec<u32> = version
+        .split(|c: char| !c.is_ascii_digit())
+        .take(3)
+        .map(|part| part.parse().unwrap_or(0))
+        .collect();
+    match numbers.as_slice() {
+        [major, minor, patch] => Some((*major, *minor, *patch)),
+        _ => None,
+    }
+}
+
+// SHOW REPLICA STATUS exists since MySQL 8.0.22 and MariaDB 10.5.1
+fn supports_replica_syntax(version: &str) -> bool {
+    match parse_version(version) {
+        Some(v) if version.contains("MariaDB") => v >= (10, 5, 1),
+        Some(v) => v >= (8, 0, 22),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn quote_identifier_wraps_plain_names() {
+        assert_eq!(quote_identifier("users"), "`users`");
+        assert_eq!(quote_identifier("order"), "`order`");
+    }
+    
+    #[test]
+    fn quote_identifier_doubles_embedded_backticks() {
+        assert_eq!(quote_identifier("a`b"), "`a``b`");
+        assert_eq!(quote_identifier("`"), "````");
+        assert_eq!(quote_identifier("x`; DROP TABLE t; --"), "`x``; DROP TABLE t; --`");
+    }
+    
+    #[test]
+    fn quote_identifier_keeps_spaces_and_unicode() {
+        assert_eq!(quote_identifier("first name"), "`first name`");
+        assert_eq!(quote_identifier("prénom"), "`prénom`");
+        assert_eq!(quote_identifier("注文 `表`"), "`注文 ``表```");
+    }
+    
+    #[test]
+    fn query_timeout_statement_matches_the_server() {
+        assert_eq!(
+            query_timeout_statement("8.0.36", 30).as_deref(),
+            Some("SET SESSION MAX_EXECUTION_TIME = 30000")
+        );
+        assert_eq!(
+            query_timeout_statement("10.11.6-MariaDB-log", 30).as_deref(),
+            Some("SET SESSION max_statement_time = 30")
+        );
+        assert_eq!(query_timeout_statement("5.6.51", 30), None);
+        assert_eq!(query_timeout_statement("10.0.38-MariaDB", 30), None);
+    }
 }
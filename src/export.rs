@@ -0,0 +1,72 @@
+// Formatting query results for use outside of rmsql
+
+use crate::database::DataRow;
+
+// SQL NULL is written as a bare NULL; Markdown has no way to set it apart
+pub fn to_markdown(columns: &[String], rows: &[DataRow]) -> String {
+    let escape = |value: &str| value.replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>");
+    
+    let header: Vec<String> = columns.iter().map(|c| escape(c)).collect();
+    let body: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|cell| escape(cell.as_deref().unwrap_or("NULL"))).collect())
+        .collect();
+    
+    // Pad every column to its widest cell so the table is readable as plain text too
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            body.iter()
+                .filter_map(|row| row.get(i))
+                .chain(std::iter::once(&header[i]))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(3)
+        })
+        .collect();
+    
+    // Numeric columns are right-aligned
+    let numeric: Vec<bool> = (0..header.len())
+        .map(|i| {
+            let mut values = rows.iter().filter_map(|row| row.get(i)).flatten().peekable();
+            values.peek().is_some() && values.all(|v| v.trim().parse::<f64>().is_ok())
+        })
+        .collect();
+    
+    let format_row = |cells: &[String]| -> String {
+        let padded: Vec<String> = widths
+            .iter()
+            .enumerate()
+            .map(|(i, width)| {
+                let cell = cells.get(i).map(|c| c.as_str()).unwrap_or("");
+                if numeric[i] {
+                    format!("{:>width$}", cell, width = width)
+                } else {
+                    format!("{:<width$}", cell, width = width)
+                }
+            })
+            .collect();
+        format!("| {} |", padded.join(" | "))
+    };
+    
+    let separator: Vec<String> = widths
+        .iter()
+        .zip(&numeric)
+        .map(|(width, is_numeric)| {
+            if *is_numeric {
+                format!("{}:", "-".repeat(width - 1))
+            } else {
+                "-".repeat(*width)
+            }
+        })
+        .collect();
+    
+    let mut lines = Vec::with_capacity(body.len() + 2);
+    lines.push(format_row(&header));
+    lines.push(format!("| {} |", separator.join(" | ")));
+    for row in &body {
+        lines.push(format_row(row));
+    }
+    
+    lines.join("\n") + "\n"
+}
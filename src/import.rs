@@ -0,0 +1,72 @@
+// Reading connection definitions written by other MySQL tools
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::connection_config::ConnectionConfig;
+
+// Option groups of a MySQL option file that the mysql client reads, in the order
+// it applies them (later groups override earlier ones)
+const MY_CNF_GROUPS: &[&str] = &["client", "mysql"];
+
+// The connection described by the [client] and [mysql] groups of an option file
+// such as ~/.my.cnf: host, port, user, password, database and ssl-mode
+pub fn read_my_cnf(path: &Path) -> Result<Vec<ConnectionConfig>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    
+    let mut options: HashMap<String, String> = HashMap::new();
+    for group in MY_CNF_GROUPS {
+        options.extend(option_group(&content, group));
+    }
+    
+    let Some(username) = options.remove("user") else {
+        bail!("{} has no user in its [client] or [mysql] group", path.display());
+    };
+    let host = options.remove("host").unwrap_or_else(|| "localhost".to_string());
+    let port = match options.remove("port") {
+        Some(port) => port.parse().with_context(|| format!("Invalid port in {}: {}", path.display(), port))?,
+        None => 3306,
+    };
+    let mut config = ConnectionConfig::new(
+        format!("{}@{} (my.cnf)", username, host),
+        host,
+        port,
+        username,
+        options.remove("password").unwrap_or_default(),
+        options.remove("database"),
+    );
+    if options.get("ssl-mode").is_some_and(|mode| mode.eq_ignore_ascii_case("disabled")) {
+        config.use_ssl = false;
+    }
+    Ok(vec![config])
+}
+
+// Key/value pairs of one [group]. Option names may use - or _, values may be
+// quoted, and # or ; start a comment line.
+fn option_group(content: &str, group: &str) -> HashMap<String, String> {
+    let mut options = HashMap::new();
+    let mut in_group = false;
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            in_group = name.trim().eq_ignore_ascii_case(group);
+            continue;
+        }
+        if !in_group {
+            continue;
+        }
+        let (key, value) = line.split_once('=').unwrap_or((line, ""));
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"').and_then(|rest| rest.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|rest| rest.strip_suffix('\'')))
+            .unwrap_or(value);
+        options.insert(key.trim().to_lowercase().replace('_', "-"), value.to_string());
+    }
+    options
+}
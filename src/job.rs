@@ -0,0 +1,95 @@
+// Database work run on a background thread so the UI keeps responding (and can
+// cancel it) while a slow query runs
+
+use anyhow::{anyhow, Result};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::database::{DatabaseManager, QueryOutput, ScriptStep, TableContents};
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+// One page of table data: total rows, the table's unfiltered row count when a filter
+// matched nothing, the offset used, and columns, their keys, rows and binary cells
+pub type TablePage = (u64, Option<u64>, usize, TableContents);
+
+pub enum JobOutput {
+    // A statement from the SQL editor
+    Query { sql: String, result: Result<QueryOutput> },
+    // The editor's last statement, re-run by auto-refresh
+    Rerun(Result<QueryOutput>),
+    // Several `;`-separated statements from the SQL editor, run in order
+    Script(Result<Vec<ScriptStep>>),
+    // EXPLAIN (ANALYZE) of a statement from the SQL editor
+    Explain { sql: String, analyze: bool, result: Result<QueryOutput> },
+    TableData(Result<TablePage>),
+    // Exact COUNT(*) of a table, asked for with #
+    RowCount { database: String, table: String, result: Result<u64> },
+}
+
+pub struct BackgroundJob {
+    pub description: String,
+    // Status to show when the job succeeds, set by the action that started it
+    pub note: Option<String>,
+    started: Instant,
+    receiver: Receiver<JobOutput>,
+    db_manager: DatabaseManager,
+}
+
+impl BackgroundJob {
+    pub fn spawn<F>(db_manager: &DatabaseManager, description: String, work: F) -> Self
+    where
+        F: FnOnce(&DatabaseManager) -> JobOutput + Send + 'static,
+    {
+        // The worker and this handle share the worker's active-connection slot
+        let worker = db_manager.background();
+        let handle = worker.clone();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // The receiver is gone when the job was cancelled; nothing to report then
+            let _ = sender.send(work(&worker));
+        });
+
+        BackgroundJob {
+            description,
+            note: None,
+            started: Instant::now(),
+            receiver,
+            db_manager: handle,
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    // The job's output once it is done
+    pub fn try_finish(&self) -> Option<Result<JobOutput>> {
+        match self.receiver.try_recv() {
+            Ok(output) => Some(Ok(output)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow!("the background query thread stopped unexpectedly"))),
+        }
+    }
+
+    // Stop waiting for the job and kill its connection so the server stops working on it
+    pub fn cancel(self) -> Result<()> {
+        self.db_manager.kill_active_connection()
+    }
+
+    pub fn status_line(&self) -> String {
+        let frame = SPINNER[(self.elapsed().as_millis() / 100) as usize % SPINNER.len()];
+        let loaded = match self.db_manager.rows_fetched() {
+            0 => String::new(),
+            rows => format!(" loaded {} rows,", rows),
+        };
+        format!(
+            "{} Running {}...{} {:.1}s (Esc or Ctrl+C to cancel)",
+            frame,
+            self.description,
+            loaded,
+            self.elapsed().as_secs_f64()
+        )
+    }
+}
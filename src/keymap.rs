@@ -0,0 +1,50 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+// A named set of keys to unbind, referenced from a connection's `keymap_profile`
+// so risky keys can be disabled on specific servers (e.g. production)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeymapProfile {
+    #[serde(default)]
+    pub unbound: Vec<String>,
+}
+
+impl KeymapProfile {
+    pub fn is_unbound(&self, key: &KeyEvent) -> bool {
+        self.unbound.contains(&key_name(key))
+    }
+}
+
+// Human-readable key name as used in profiles, e.g. "D", "Ctrl+p", "F5", "Enter"
+pub fn key_name(key: &KeyEvent) -> String {
+    let base = match key.code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::BackTab => "BackTab".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Delete => "Delete".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        other => format!("{:?}", other),
+    };
+    
+    let mut name = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        name.push_str("Ctrl+");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        name.push_str("Alt+");
+    }
+    name.push_str(&base);
+    name
+}
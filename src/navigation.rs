@@ -1,13 +1,46 @@
+use ratatui::style::Color;
 use ratatui::widgets::{ListState, TableState};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Clone, PartialEq)]
+use crate::command;
+use crate::completion::Completion;
+use crate::preferences::PreferencesEditor;
+use crate::search::SearchPattern;
+use crate::database::{self, BinaryCells, BinlogStatus, ColumnDetail, ColumnKey, DataRow, ExplainComparison, ForeignKey, IndexInfo, ProcessInfo, ReplicationStatus};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ViewMode {
     Databases,
     Tables,
     TableData,
     SqlEditor,
+    Replication,
+    Binlog,
+    ProcessList,
 }
 
+pub const DEFAULT_ROW_LIMIT: usize = 100;
+pub const MAX_ROW_LIMIT: usize = 1_000_000;
+// Narrowest a column gets in compact mode; wider tables scroll horizontally instead
+pub const COMPACT_COLUMN_WIDTH: u16 = 14;
+// Expanded mode: width of columns without a manual width, and the resizing bounds
+pub const EXPANDED_COLUMN_WIDTH: u16 = 20;
+pub const MIN_COLUMN_WIDTH: u16 = 6;
+const COLUMN_RESIZE_STEP: i32 = 4;
+
+// Entries in the editor's "recently run" quick list
+pub const RECENT_QUERIES_SHOWN: usize = 3;
+
+// Text lines of the SQL editor input box: the default preferred height and its upper bound
+pub const DEFAULT_SQL_INPUT_LINES: u16 = 3;
+pub const MAX_SQL_INPUT_LINES: u16 = 30;
+
+// Foreign key jumps remembered for Esc, and locations kept in the back/forward
+// history; the oldest are dropped
+const MAX_JUMP_STACK: usize = 50;
+const MAX_HISTORY: usize = 100;
+
 pub struct NavigationState {
     pub mode: ViewMode,
     pub current_database: Option<String>,
@@ -16,19 +49,156 @@ pub struct NavigationState {
     // Data storage
     pub databases: Vec<String>,
     pub tables: Vec<String>,
+    // Favorites of the current connection/database, pinned to the top of the lists
+    pub favorite_databases: HashSet<String>,
+    pub favorite_tables: HashSet<String>,
     pub table_columns: Vec<String>,
-    pub table_rows: Vec<Vec<String>>,
+    pub table_numeric_columns: Vec<bool>,
+    // Columns declared as JSON, marked in the header and pretty-printed in the cell popup
+    pub table_json_columns: Vec<bool>,
+    // Primary/unique key membership per column, marked in the header
+    pub table_column_keys: Vec<ColumnKey>,
+    pub table_rows: Vec<DataRow>,
+    // Raw bytes of the table_rows cells that hold binary data (shown as hex previews)
+    pub binary_cells: BinaryCells,
+    pub replication_status: Option<ReplicationStatus>,
+    pub binlog_status: Option<BinlogStatus>,
+    pub process_list: Vec<ProcessInfo>,
+    pub process_list_state: TableState,
+    pub affected_rows_preview: Option<SqlResult>,
+    
+    // Full-value popup for the selected cell: (column, value) and its scroll offset
+    pub cell_detail: Option<(String, Option<String>)>,
+    pub cell_detail_scroll: u16,
+    // The cell's raw bytes when it holds binary data
+    pub cell_detail_bytes: Option<Vec<u8>>,
+    // The cell comes from a JSON column, so even scalar values are parsed as JSON
+    pub cell_detail_json: bool,
+    // Display toggles of the cell popup, kept between popups; the horizontal
+    // scroll applies when wrapping is off
+    pub cell_detail_wrap: bool,
+    pub cell_detail_line_numbers: bool,
+    // Show JSON exactly as stored instead of pretty-printed
+    pub cell_detail_raw: bool,
+    pub cell_detail_hscroll: u16,
+    
+    // Preferences editor popup
+    pub preferences_editor: Option<PreferencesEditor>,
+    
+    // Popup with a table's CREATE statement: (table, DDL)
+    pub ddl_popup: Option<(String, String)>,
+    // Labeled rows of the server/session info panel
+    pub server_info: Option<Vec<(String, String)>>,
+    pub ddl_popup_scroll: u16,
+    
+    // Popup with a table's columns, indexes and foreign keys
+    pub schema_popup: Option<SchemaPopup>,
+    
+    // Effective row limit for table data and editor queries
+    // None fetches every row (no LIMIT)
+    pub row_limit: Option<usize>,
+    
+    // Pagination for table data
+    pub page_offset: usize,
+    pub table_total_rows: Option<u64>,
+    // Exact row counts from # for this session, with when they were taken, by (database, table)
+    pub row_counts: HashMap<(String, String), (u64, chrono::DateTime<chrono::Local>)>,
+    // Row count without the WHERE filter, known when the filter matched no rows
+    pub table_unfiltered_rows: Option<u64>,
     
     // Table display settings
     pub expanded_columns: bool,
     pub horizontal_scroll: usize,
     pub visible_columns: usize,
+    // How many compact-width columns fit the terminal, updated before each draw
+    pub compact_columns: usize,
+    // Manual widths set with </> in expanded mode, by bare column name
+    pub column_widths: HashMap<String, u16>,
+    // Columns left out of the grid with the column picker (H), by bare column name
+    pub hidden_columns: HashSet<String>,
+    // Selected line of the column picker while it is open
+    pub column_picker: Option<usize>,
+    pub focused_column: Option<usize>,
+    pub sort_column: Option<String>,
+    pub sort_desc: bool,
+    pub table_filter: Option<String>,
+    
+    // Search within the loaded rows: the term, its compiled pattern and the indices
+    // of the rows that contain it
+    pub search_term: Option<String>,
+    pub search_pattern: Option<SearchPattern>,
+    pub search_case_sensitive: bool,
+    pub search_regex: bool,
+    pub search_matches: Vec<usize>,
+    
+    // Filter and offset of the page currently shown, restored when loading another fails
+    loaded_table_view: Option<(Option<String>, usize)>,
+    
+    // Incremental filter over the Databases/Tables list of the current mode;
+    // list selections index into `filtered_indices` while it is active
+    pub list_filter: String,
+    pub filtered_indices: Vec<usize>,
+    
+    // Row estimate and data length per table, shown in the Tables list when toggled on
+    pub show_table_stats: bool,
+    pub table_stats: HashMap<String, (Option<u64>, u64)>,
+    
+    // Show DATETIME values converted from UTC to the local timezone
+    pub local_time: bool,
+    // Mark empty and all-whitespace strings, and trailing whitespace in the cell popup
+    pub blank_markers: bool,
+    // Prefix list and data rows with their distance from the selected row
+    pub relative_numbers: bool,
+    // Interval shown in the status bar while auto-refresh is on
+    pub auto_refresh_secs: Option<u64>,
+    
+    // Name and color of the connection's header badge; None is a neutral badge
+    pub connection_name: String,
+    pub connection_color: Option<Color>,
+    // The connection blocks writes; shown as a badge in the header
+    pub read_only: bool,
+    // A transaction is open on the pinned connection (BEGIN .. COMMIT/ROLLBACK)
+    pub in_transaction: bool,
+    
+    // Vim-style count typed before a movement key, e.g. the 5 in `5j`
+    pub pending_count: Option<usize>,
+    // A `g` was pressed and the next key may complete `gd` or `g1`-`g3`
+    pub pending_g: bool,
+    // Row selected before a `g` (which moves to the top right away), so a following `d` still acts on it
+    pub pending_g_row: Option<usize>,
+    
+    // Where foreign key jumps started; Esc returns to the latest
+    pub jump_stack: Vec<NavState>,
+    // Every location visited, for Ctrl+O/Tab (back/forward); history_index is the current one
+    pub history: Vec<NavState>,
+    pub history_index: usize,
+    
+    // Single-line input prompt shown in the status bar
+    pub input_prompt: Option<InputPrompt>,
     
     // SQL Editor
     pub sql_input: String,
+    // Preferred height of the input box in text lines; a longer buffer grows it further
+    pub sql_input_lines: u16,
+    pub completion: Option<Completion>,
+    // Column names per table of the current database, loaded lazily for completion
+    pub column_cache: HashMap<String, Vec<String>>,
     pub sql_history: Vec<String>,
     pub sql_history_index: Option<usize>,
+    // What was typed before stepping into the history, given back past its end
+    pub sql_history_stash: Option<String>,
+    pub recent_queries_selected: Option<usize>,
+    pub snippet_picker: Option<SnippetPicker>,
+    pub last_executed_sql: Option<String>,
     pub sql_result: Option<SqlResult>,
+    // Lines scrolled in the error box of a failed statement
+    pub sql_error_scroll: u16,
+    pub explain_comparison: Option<ExplainComparison>,
+    
+    // Query queue and the tabbed results of its last run
+    pub query_queue: Vec<String>,
+    pub result_tabs: Vec<ResultTab>,
+    pub active_result_tab: usize,
     
     // List states for UI
     pub database_list_state: ListState,
@@ -36,13 +206,243 @@ pub struct NavigationState {
     pub data_table_state: TableState,
 }
 
+// Saved snippets listed in the SQL editor, as (name, SQL) sorted by name
+#[derive(Debug, Clone)]
+pub struct SnippetPicker {
+    pub snippets: Vec<(String, String)>,
+    pub selected: usize,
+}
+
+impl SnippetPicker {
+    pub fn move_selection(&mut self, down: bool) {
+        self.selected = if down {
+            (self.selected + 1).min(self.snippets.len().saturating_sub(1))
+        } else {
+            self.selected.saturating_sub(1)
+        };
+    }
+    
+    pub fn selected_snippet(&self) -> Option<&(String, String)> {
+        self.snippets.get(self.selected)
+    }
+    
+    // Drop the selected entry after it was deleted from the config
+    pub fn remove_selected(&mut self) {
+        if self.selected < self.snippets.len() {
+            self.snippets.remove(self.selected);
+            self.selected = self.selected.min(self.snippets.len().saturating_sub(1));
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SqlResult {
     pub columns: Vec<String>,
-    pub rows: Vec<Vec<String>>,
+    pub numeric_columns: Vec<bool>,
+    pub rows: Vec<DataRow>,
     pub message: String,
 }
 
+// One loaded row of a table, identified by its primary/unique key
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowKey {
+    pub table: String,
+    // Index into table_rows
+    pub row: usize,
+    pub columns: Vec<String>,
+    pub values: Vec<String>,
+}
+
+impl RowKey {
+    // id = '5' AND code = 'x', for confirmations
+    pub fn description(&self) -> String {
+        self.columns
+            .iter()
+            .zip(&self.values)
+            .map(|(column, value)| format!("{} = '{}'", column, value))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+}
+
+// A single-cell change, targeted at one row through its key
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellEdit {
+    pub key: RowKey,
+    pub column: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptKind {
+    JumpToColumn,
+    DumpTableData { table: String, include_ddl: bool },
+    ConfirmUnscopedWrite { sql: String, target: String },
+    FilterRows,
+    FilterList,
+    SetRowLimit { save_as_default: bool },
+    Search { case_sensitive: bool, regex: bool },
+    EditCell { edit: CellEdit, set_null: bool },
+    ConfirmCellEdit(CellEdit),
+    ConfirmRowDelete(RowKey),
+    ConfirmQuitTransaction,
+    ConfirmKill { id: u64, user: String },
+    SaveSnippet { sql: String },
+    // Collects the values of the ? placeholders of `sql` one by one
+    QueryParam { sql: String, hints: Vec<String>, values: Vec<Option<String>>, set_null: bool },
+    Command,
+}
+
+#[derive(Debug, Clone)]
+pub struct InputPrompt {
+    pub kind: PromptKind,
+    pub input: String,
+}
+
+impl InputPrompt {
+    pub fn label(&self) -> String {
+        match &self.kind {
+            PromptKind::JumpToColumn => "Jump to column".to_string(),
+            PromptKind::DumpTableData { table, include_ddl } => format!(
+                "Dump '{}' to file (.sql or .csv) [Tab: DDL header {}]",
+                table,
+                if *include_ddl { "on" } else { "off" }
+            ),
+            PromptKind::FilterRows => "WHERE (Enter apply, Esc clear filter)".to_string(),
+            PromptKind::FilterList => "Filter (fuzzy; Enter keep, Esc clear)".to_string(),
+            PromptKind::EditCell { edit, set_null } => format!(
+                "{} [Tab: {}]",
+                edit.column,
+                if *set_null { "set NULL (input ignored)" } else { "set NULL off" }
+            ),
+            PromptKind::ConfirmCellEdit(edit) => format!(
+                "UPDATE {} SET {} = {} WHERE {} - Enter to run, Esc to cancel",
+                edit.key.table,
+                edit.column,
+                edit.value.as_ref().map_or("NULL".to_string(), |value| format!("'{}'", value)),
+                edit.key.description()
+            ),
+            PromptKind::ConfirmRowDelete(key) => format!(
+                "DELETE FROM {} WHERE {} - type y and Enter to delete, Esc to cancel",
+                key.table,
+                key.description()
+            ),
+            PromptKind::Search { case_sensitive, regex } => format!(
+                "Search [Tab: case {}, Ctrl+R: {}]",
+                if *case_sensitive { "sensitive" } else { "insensitive" },
+                if *regex { "regex" } else { "plain text" }
+            ),
+            PromptKind::SetRowLimit { save_as_default } => format!(
+                "Row limit (empty = no limit) [Tab: save as default {}]",
+                if *save_as_default { "yes" } else { "no" }
+            ),
+            PromptKind::Command => format!("Command ({})", command::COMMAND_HELP),
+            PromptKind::SaveSnippet { .. } => "Save query as snippet named (an existing name is replaced)".to_string(),
+            PromptKind::QueryParam { hints, values, set_null, .. } => {
+                let index = values.len();
+                let hint = hints.get(index).filter(|hint| !hint.is_empty()).map(|hint| format!(" ({})", hint)).unwrap_or_default();
+                format!(
+                    "Parameter {} of {}{} [Tab: {}]",
+                    index + 1,
+                    hints.len(),
+                    hint,
+                    if *set_null { "NULL (input ignored)" } else { "NULL off" }
+                )
+            },
+            PromptKind::ConfirmKill { id, user } => format!(
+                "Kill thread {} of {} - type q to KILL QUERY or c to KILL the connection, then Enter (Esc cancels)",
+                id, user
+            ),
+            PromptKind::ConfirmQuitTransaction => {
+                "Transaction still open - type c to COMMIT or r to ROLLBACK, then Enter to quit (Esc stays)".to_string()
+            },
+            PromptKind::ConfirmUnscopedWrite { target, .. } => format!(
+                "No WHERE clause - this changes EVERY row. Type '{}' to run it anyway",
+                target
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResultTab {
+    pub sql: String,
+    pub result: SqlResult,
+}
+
+// A place to come back to: the view, its database/table and what was selected there
+#[derive(Debug, Clone)]
+pub struct NavState {
+    pub mode: ViewMode,
+    pub database: Option<String>,
+    pub table: Option<String>,
+    pub filter: Option<String>,
+    pub sort: Option<(String, bool)>,
+    pub page_offset: usize,
+    // List entry or table row
+    pub selected: Option<usize>,
+    pub focused_column: Option<usize>,
+}
+
+impl NavState {
+    // Same view of the same database/table; selection, filter and paging don't count
+    fn same_location(&self, other: &NavState) -> bool {
+        self.mode == other.mode
+            && match self.mode {
+                ViewMode::Tables => self.database == other.database,
+                ViewMode::TableData => self.database == other.database && self.table == other.table,
+                _ => true,
+            }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SchemaPopup {
+    pub table: String,
+    pub columns: Vec<ColumnDetail>,
+    pub indexes: Vec<IndexInfo>,
+    pub foreign_keys: Vec<ForeignKey>,
+    // First column row shown
+    pub scroll: usize,
+    // Foreign key that Enter jumps through
+    pub selected_foreign_key: usize,
+}
+
+impl SchemaPopup {
+    pub fn new(table: String, columns: Vec<ColumnDetail>, indexes: Vec<IndexInfo>, foreign_keys: Vec<ForeignKey>) -> Self {
+        SchemaPopup {
+            table,
+            columns,
+            indexes,
+            foreign_keys,
+            scroll: 0,
+            selected_foreign_key: 0,
+        }
+    }
+    
+    pub fn scroll(&mut self, lines: i32) {
+        let last = self.columns.len().saturating_sub(1);
+        self.scroll = (self.scroll as i32 + lines).clamp(0, last as i32) as usize;
+    }
+    
+    // Tab/BackTab cycle through the foreign keys
+    pub fn cycle_foreign_key(&mut self, forward: bool) {
+        let count = self.foreign_keys.len();
+        if count == 0 {
+            return;
+        }
+        self.selected_foreign_key = if forward {
+            (self.selected_foreign_key + 1) % count
+        } else {
+            (self.selected_foreign_key + count - 1) % count
+        };
+    }
+    
+    pub fn selected_foreign_key(&self) -> Option<&ForeignKey> {
+        self.foreign_keys.get(self.selected_foreign_key)
+    }
+}
+
 impl NavigationState {
     pub fn new() -> Self {
         let mut nav = NavigationState {
@@ -51,18 +451,92 @@ impl NavigationState {
             current_table: None,
             databases: Vec::new(),
             tables: Vec::new(),
+            favorite_databases: HashSet::new(),
+            favorite_tables: HashSet::new(),
             table_columns: Vec::new(),
+            table_numeric_columns: Vec::new(),
+            table_json_columns: Vec::new(),
+            table_column_keys: Vec::new(),
             table_rows: Vec::new(),
+            binary_cells: BinaryCells::new(),
+            replication_status: None,
+            binlog_status: None,
+            affected_rows_preview: None,
+            cell_detail: None,
+            cell_detail_scroll: 0,
+            cell_detail_bytes: None,
+            cell_detail_json: false,
+            cell_detail_wrap: true,
+            cell_detail_line_numbers: false,
+            cell_detail_raw: false,
+            cell_detail_hscroll: 0,
+            preferences_editor: None,
+            ddl_popup: None,
+            server_info: None,
+            ddl_popup_scroll: 0,
+            schema_popup: None,
+            row_limit: Some(DEFAULT_ROW_LIMIT),
+            page_offset: 0,
+            table_total_rows: None,
+            row_counts: HashMap::new(),
+            table_unfiltered_rows: None,
             expanded_columns: false,
             horizontal_scroll: 0,
             visible_columns: 3, // Default number of visible columns when expanded
+            compact_columns: usize::MAX,
+            column_widths: HashMap::new(),
+            hidden_columns: HashSet::new(),
+            column_picker: None,
+            focused_column: None,
+            sort_column: None,
+            sort_desc: false,
+            table_filter: None,
+            search_term: None,
+            search_pattern: None,
+            search_case_sensitive: false,
+            search_regex: false,
+            search_matches: Vec::new(),
+            loaded_table_view: None,
+            list_filter: String::new(),
+            filtered_indices: Vec::new(),
+            show_table_stats: false,
+            table_stats: HashMap::new(),
+            local_time: false,
+            blank_markers: true,
+            relative_numbers: false,
+            auto_refresh_secs: None,
+            connection_name: String::new(),
+            connection_color: None,
+            read_only: false,
+            in_transaction: false,
+            pending_count: None,
+            pending_g: false,
+            pending_g_row: None,
+            jump_stack: Vec::new(),
+            history: Vec::new(),
+            history_index: 0,
+            input_prompt: None,
             sql_input: String::new(),
+            sql_input_lines: DEFAULT_SQL_INPUT_LINES,
+            completion: None,
+            column_cache: HashMap::new(),
             sql_history: Vec::new(),
             sql_history_index: None,
+            sql_history_stash: None,
+            snippet_picker: None,
+            recent_queries_selected: None,
+            last_executed_sql: None,
             sql_result: None,
+            sql_error_scroll: 0,
+            explain_comparison: None,
+            query_queue: Vec::new(),
+            result_tabs: Vec::new(),
+            active_result_tab: 0,
             database_list_state: ListState::default(),
             table_list_state: ListState::default(),
             data_table_state: TableState::default(),
+            process_list: Vec::new(),
+            process_list_state: TableState::default(),
         };
         
         // Initialize first item selected
@@ -93,8 +567,12 @@ impl NavigationState {
                     self.data_table_state.select(Some(current - 1));
                 }
             },
-            ViewMode::SqlEditor => {
-                // No movement in SQL editor mode
+            ViewMode::ProcessList => {
+                let current = self.process_list_state.selected().unwrap_or(0);
+                self.process_list_state.select(Some(current.saturating_sub(1)));
+            },
+            ViewMode::SqlEditor | ViewMode::Replication | ViewMode::Binlog => {
+                // No movement in SQL editor or server status views
             },
         }
     }
@@ -103,13 +581,13 @@ impl NavigationState {
         match self.mode {
             ViewMode::Databases => {
                 let current = self.database_list_state.selected().unwrap_or(0);
-                if current < self.databases.len().saturating_sub(1) {
+                if current < self.visible_len(self.databases.len()).saturating_sub(1) {
                     self.database_list_state.select(Some(current + 1));
                 }
             },
             ViewMode::Tables => {
                 let current = self.table_list_state.selected().unwrap_or(0);
-                if current < self.tables.len().saturating_sub(1) {
+                if current < self.visible_len(self.tables.len()).saturating_sub(1) {
                     self.table_list_state.select(Some(current + 1));
                 }
             },
@@ -119,9 +597,101 @@ impl NavigationState {
                     self.data_table_state.select(Some(current + 1));
                 }
             },
-            ViewMode::SqlEditor => {
-                // No movement in SQL editor mode
+            ViewMode::ProcessList => {
+                let current = self.process_list_state.selected().unwrap_or(0);
+                if current < self.process_list.len().saturating_sub(1) {
+                    self.process_list_state.select(Some(current + 1));
+                }
+            },
+            ViewMode::SqlEditor | ViewMode::Replication | ViewMode::Binlog => {
+                // No movement in SQL editor or server status views
+            },
+        }
+    }
+    
+    // Select an entry of the current list or data table by its shown position, e.g. from a click
+    pub fn select_position(&mut self, position: usize) {
+        match self.mode {
+            ViewMode::Databases if position < self.visible_len(self.databases.len()) => {
+                self.database_list_state.select(Some(position));
+            },
+            ViewMode::Tables if position < self.visible_len(self.tables.len()) => {
+                self.table_list_state.select(Some(position));
+            },
+            ViewMode::TableData if position < self.table_rows.len() => {
+                self.data_table_state.select(Some(position));
+            },
+            _ => {}
+        }
+    }
+    
+    pub fn selected_position(&self) -> Option<usize> {
+        match self.mode {
+            ViewMode::Databases => self.database_list_state.selected(),
+            ViewMode::Tables => self.table_list_state.selected(),
+            ViewMode::TableData => self.data_table_state.selected(),
+            _ => None,
+        }
+    }
+    
+    // Grow or shrink the SQL input box by a line; returns the new height
+    pub fn resize_sql_input(&mut self, grow: bool) -> u16 {
+        self.sql_input_lines = if grow {
+            (self.sql_input_lines + 1).min(MAX_SQL_INPUT_LINES)
+        } else {
+            self.sql_input_lines.saturating_sub(1).max(1)
+        };
+        self.sql_input_lines
+    }
+    
+    // Whether a popup or prompt is drawn over the main view
+    pub fn has_popup(&self) -> bool {
+        self.affected_rows_preview.is_some()
+            || self.cell_detail.is_some()
+            || self.preferences_editor.is_some()
+            || self.ddl_popup.is_some()
+            || self.server_info.is_some()
+            || self.schema_popup.is_some()
+            || self.input_prompt.is_some()
+            || self.recent_queries_selected.is_some()
+            || self.snippet_picker.is_some()
+            || self.column_picker.is_some()
+    }
+    
+    // Append a digit to the pending count (capped at MAX_ROW_LIMIT); returns the new count
+    pub fn push_count_digit(&mut self, digit: u32) -> usize {
+        let count = (self.pending_count.unwrap_or(0).saturating_mul(10) + digit as usize).min(MAX_ROW_LIMIT);
+        self.pending_count = Some(count);
+        count
+    }
+    
+    // Select the 1-based row/item `position`, clamped to the last one (vim's `{count}G`)
+    pub fn move_to_position(&mut self, position: usize) {
+        let index = position.saturating_sub(1);
+        match self.mode {
+            ViewMode::Databases => {
+                let len = self.visible_len(self.databases.len());
+                if len > 0 {
+                    self.database_list_state.select(Some(index.min(len - 1)));
+                }
+            },
+            ViewMode::Tables => {
+                let len = self.visible_len(self.tables.len());
+                if len > 0 {
+                    self.table_list_state.select(Some(index.min(len - 1)));
+                }
+            },
+            ViewMode::TableData => {
+                if !self.table_rows.is_empty() {
+                    self.data_table_state.select(Some(index.min(self.table_rows.len() - 1)));
+                }
             },
+            ViewMode::ProcessList => {
+                if !self.process_list.is_empty() {
+                    self.process_list_state.select(Some(index.min(self.process_list.len() - 1)));
+                }
+            },
+            ViewMode::SqlEditor | ViewMode::Replication | ViewMode::Binlog => {} // No action needed
         }
     }
     
@@ -130,20 +700,23 @@ impl NavigationState {
             ViewMode::Databases => self.database_list_state.select(Some(0)),
             ViewMode::Tables => self.table_list_state.select(Some(0)),
             ViewMode::TableData => self.data_table_state.select(Some(0)),
-            ViewMode::SqlEditor => {} // No action needed
+            ViewMode::ProcessList => self.process_list_state.select(Some(0)),
+            ViewMode::SqlEditor | ViewMode::Replication | ViewMode::Binlog => {} // No action needed
         }
     }
     
     pub fn move_to_bottom(&mut self) {
         match self.mode {
             ViewMode::Databases => {
-                if !self.databases.is_empty() {
-                    self.database_list_state.select(Some(self.databases.len() - 1));
+                let len = self.visible_len(self.databases.len());
+                if len > 0 {
+                    self.database_list_state.select(Some(len - 1));
                 }
             },
             ViewMode::Tables => {
-                if !self.tables.is_empty() {
-                    self.table_list_state.select(Some(self.tables.len() - 1));
+                let len = self.visible_len(self.tables.len());
+                if len > 0 {
+                    self.table_list_state.select(Some(len - 1));
                 }
             },
             ViewMode::TableData => {
@@ -151,62 +724,463 @@ impl NavigationState {
                     self.data_table_state.select(Some(self.table_rows.len() - 1));
                 }
             },
-            ViewMode::SqlEditor => {} // No action needed
+            ViewMode::ProcessList => {
+                if !self.process_list.is_empty() {
+                    self.process_list_state.select(Some(self.process_list.len() - 1));
+                }
+            },
+            ViewMode::SqlEditor | ViewMode::Replication | ViewMode::Binlog => {} // No action needed
         }
     }
     
     pub fn set_mode(&mut self, mode: ViewMode) {
+        if mode != self.mode {
+            self.clear_list_filter();
+        }
         self.mode = mode;
     }
     
+    fn list_items(&self) -> &[String] {
+        match self.mode {
+            ViewMode::Databases => &self.databases,
+            ViewMode::Tables => &self.tables,
+            _ => &[],
+        }
+    }
+    
+    fn list_state_mut(&mut self) -> Option<&mut ListState> {
+        match self.mode {
+            ViewMode::Databases => Some(&mut self.database_list_state),
+            ViewMode::Tables => Some(&mut self.table_list_state),
+            _ => None,
+        }
+    }
+    
+    // Number of entries shown in the list of the current mode
+    fn visible_len(&self, full_len: usize) -> usize {
+        if self.list_filter.is_empty() {
+            full_len
+        } else {
+            self.filtered_indices.len()
+        }
+    }
+    
+    // Underlying index of a list position in `mode`'s list
+    fn list_index(&self, mode: ViewMode, position: usize) -> Option<usize> {
+        if self.mode == mode && !self.list_filter.is_empty() {
+            self.filtered_indices.get(position).copied()
+        } else {
+            Some(position)
+        }
+    }
+    
+    // Entries of `mode`'s list as currently shown, best matches first while filtering
+    pub fn visible_list_items<'a>(&'a self, mode: ViewMode, items: &'a [String]) -> Vec<&'a String> {
+        if self.mode == mode && !self.list_filter.is_empty() {
+            self.filtered_indices.iter().filter_map(|&i| items.get(i)).collect()
+        } else {
+            items.iter().collect()
+        }
+    }
+    
+    pub fn set_list_filter(&mut self, query: &str) {
+        self.list_filter = query.to_string();
+        self.refresh_list_filter();
+        if let Some(state) = self.list_state_mut() {
+            state.select(Some(0));
+        }
+    }
+    
+    fn refresh_list_filter(&mut self) {
+        if self.list_filter.is_empty() {
+            self.filtered_indices.clear();
+            return;
+        }
+        let mut scored: Vec<(usize, usize)> = self
+            .list_items()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| match_score(name, &self.list_filter).map(|score| (score, i)))
+            .collect();
+        scored.sort();
+        self.filtered_indices = scored.into_iter().map(|(_, i)| i).collect();
+    }
+    
+    // Drop the filter, keeping the highlighted entry selected in the full list
+    pub fn clear_list_filter(&mut self) {
+        if self.list_filter.is_empty() {
+            return;
+        }
+        let position = self.list_state_mut().and_then(|state| state.selected());
+        let selected = position.and_then(|position| self.list_index(self.mode.clone(), position));
+        self.list_filter.clear();
+        self.filtered_indices.clear();
+        if let Some(state) = self.list_state_mut() {
+            state.select(Some(selected.unwrap_or(0)));
+        }
+    }
+    
+    // Entering the database that is already open keeps its table list and selection
     pub fn set_current_database(&mut self, database: String) {
+        if self.current_database.as_deref() == Some(database.as_str()) {
+            return;
+        }
         self.current_database = Some(database);
         self.current_table = None; // Reset table when changing database
         self.tables.clear();
+        self.table_stats.clear();
+        self.column_cache.clear();
         self.table_rows.clear();
+        self.binary_cells.clear();
         self.table_columns.clear();
         self.table_list_state.select(Some(0));
         self.data_table_state.select(Some(0));
     }
     
+    pub fn nav_state(&self) -> NavState {
+        let selected = match self.mode {
+            ViewMode::Databases => self.database_list_state.selected(),
+            ViewMode::Tables => self.table_list_state.selected(),
+            ViewMode::TableData => self.data_table_state.selected(),
+            _ => None,
+        };
+        NavState {
+            mode: self.mode.clone(),
+            database: self.current_database.clone(),
+            table: self.current_table.clone(),
+            filter: self.table_filter.clone(),
+            sort: self.sort_column.clone().map(|column| (column, self.sort_desc)),
+            page_offset: self.page_offset,
+            selected,
+            focused_column: self.focused_column,
+        }
+    }
+    
+    // Switch to a remembered location; the caller reloads its data
+    pub fn restore_nav_state(&mut self, state: NavState) {
+        if let Some(database) = state.database {
+            if self.current_database.as_deref() != Some(database.as_str()) {
+                self.set_current_database(database);
+            }
+        }
+        // Mode first: leaving a filtered list re-maps its selection
+        self.set_mode(state.mode.clone());
+        match state.mode {
+            ViewMode::Databases => self.database_list_state.select(state.selected),
+            ViewMode::Tables => self.table_list_state.select(state.selected),
+            ViewMode::TableData => {
+                if let Some(table) = state.table {
+                    self.set_current_table(table);
+                }
+                self.table_filter = state.filter;
+                if let Some((column, desc)) = state.sort {
+                    self.sort_column = Some(column);
+                    self.sort_desc = desc;
+                }
+                self.page_offset = state.page_offset;
+                self.data_table_state.select(state.selected);
+                self.focused_column = state.focused_column;
+            },
+            _ => {}
+        }
+    }
+    
+    // Remember the current table view before following a foreign key away from it
+    pub fn push_jump_origin(&mut self) {
+        if self.jump_stack.len() >= MAX_JUMP_STACK {
+            self.jump_stack.remove(0);
+        }
+        self.jump_stack.push(self.nav_state());
+    }
+    
+    // Add the current location to the history when a key moved away from `before`.
+    // Anything ahead of the current entry is dropped, like following a link in a browser.
+    pub fn record_history(&mut self, before: NavState) {
+        let after = self.nav_state();
+        if after.same_location(&before)
+            || self.history.get(self.history_index).is_some_and(|entry| entry.same_location(&after))
+        {
+            return;
+        }
+        
+        self.history.truncate(self.history_index + 1);
+        // Keep the selection the previous location had when it was left
+        match self.history.last_mut() {
+            Some(entry) if entry.same_location(&before) => *entry = before,
+            _ => self.history.push(before),
+        }
+        self.history.push(after);
+        if self.history.len() > MAX_HISTORY {
+            self.history.drain(..self.history.len() - MAX_HISTORY);
+        }
+        self.history_index = self.history.len() - 1;
+    }
+    
+    // Step back or forward through the history; the current entry keeps its selection
+    pub fn step_history(&mut self, forward: bool) -> Option<NavState> {
+        let target = if forward {
+            self.history_index + 1
+        } else {
+            self.history_index.checked_sub(1)?
+        };
+        if target >= self.history.len() {
+            return None;
+        }
+        let current = self.nav_state();
+        if let Some(entry) = self.history.get_mut(self.history_index) {
+            if entry.same_location(&current) {
+                *entry = current;
+            }
+        }
+        self.history_index = target;
+        Some(self.history[target].clone())
+    }
+    
     pub fn set_current_table(&mut self, table: String) {
         self.current_table = Some(table);
         self.table_rows.clear();
+        self.binary_cells.clear();
         self.table_columns.clear();
+        self.table_numeric_columns.clear();
+        self.table_json_columns.clear();
+        self.table_column_keys.clear();
+        self.focused_column = None;
+        self.horizontal_scroll = 0;
+        self.column_widths.clear();
+        self.hidden_columns.clear();
+        self.column_picker = None;
+        self.sort_column = None;
+        self.sort_desc = false;
+        self.table_filter = None;
+        self.page_offset = 0;
+        self.table_total_rows = None;
+        self.table_unfiltered_rows = None;
+        self.loaded_table_view = None;
         self.data_table_state.select(Some(0));
     }
     
-    pub fn set_databases(&mut self, databases: Vec<String>) {
+    // Whether a page of the current table has been shown yet
+    pub fn is_table_loaded(&self) -> bool {
+        self.loaded_table_view.is_some()
+    }
+    
+    pub fn mark_table_loaded(&mut self) {
+        self.loaded_table_view = Some((self.table_filter.clone(), self.page_offset));
+    }
+    
+    // Go back to the filter and offset of the page on screen; returns whether the filter changed
+    pub fn restore_loaded_table_view(&mut self) -> bool {
+        match self.loaded_table_view.clone() {
+            Some((filter, offset)) => {
+                let filter_changed = filter != self.table_filter;
+                self.table_filter = filter;
+                self.page_offset = offset;
+                filter_changed
+            },
+            None => false,
+        }
+    }
+    
+    pub fn set_databases(&mut self, mut databases: Vec<String>) {
+        pin_favorites(&mut databases, &self.favorite_databases);
+        let previous = self.get_selected_database().cloned();
         self.databases = databases;
-        if !self.databases.is_empty() && self.database_list_state.selected().is_none() {
-            self.database_list_state.select(Some(0));
+        self.refresh_list_filter();
+        if let Some(position) = self.kept_selection(ViewMode::Databases, &self.databases, previous, self.database_list_state.selected()) {
+            self.database_list_state.select(Some(position));
+        }
+    }
+    
+    // Position to highlight after a list was reloaded: the entry that was selected if it
+    // is still there, otherwise the same row clamped to the new length
+    fn kept_selection(&self, mode: ViewMode, items: &[String], previous: Option<String>, position: Option<usize>) -> Option<usize> {
+        let visible = self.visible_list_items(mode, items);
+        if visible.is_empty() {
+            return None;
+        }
+        previous
+            .and_then(|name| visible.iter().position(|item| **item == name))
+            .or(position.map(|position| position.min(visible.len() - 1)))
+            .or(Some(0))
+    }
+    
+    // Highlight a database/table by name in its list, e.g. when restoring the last session
+    pub fn select_database(&mut self, name: &str) {
+        if let Some(index) = self.databases.iter().position(|db| db == name) {
+            self.database_list_state.select(Some(index));
+        }
+    }
+    
+    pub fn select_table(&mut self, name: &str) {
+        if let Some(index) = self.tables.iter().position(|table| table == name) {
+            self.table_list_state.select(Some(index));
+        }
+    }
+    
+    // Flip the favorite flag of the selected database or table and re-pin the list,
+    // keeping that entry selected. Returns its name and the new flag.
+    pub fn toggle_selected_favorite(&mut self) -> Option<(String, bool)> {
+        let (name, favorites) = match self.mode {
+            ViewMode::Databases => (self.get_selected_database()?.clone(), &mut self.favorite_databases),
+            ViewMode::Tables => (self.get_selected_table()?.clone(), &mut self.favorite_tables),
+            _ => return None,
+        };
+        let favorite = !favorites.remove(&name);
+        if favorite {
+            favorites.insert(name.clone());
         }
+        
+        let position = if self.mode == ViewMode::Databases {
+            let databases = std::mem::take(&mut self.databases);
+            self.set_databases(databases);
+            self.visible_list_items(ViewMode::Databases, &self.databases).iter().position(|db| **db == name)
+        } else {
+            let tables = std::mem::take(&mut self.tables);
+            self.set_tables(tables);
+            self.visible_list_items(ViewMode::Tables, &self.tables).iter().position(|table| **table == name)
+        };
+        if position.is_some() {
+            match self.mode {
+                ViewMode::Databases => self.database_list_state.select(position),
+                _ => self.table_list_state.select(position),
+            }
+        }
+        Some((name, favorite))
+    }
+    
+    pub fn set_table_stats(&mut self, stats: Vec<(String, Option<u64>, u64)>) {
+        self.table_stats = stats
+            .into_iter()
+            .map(|(name, rows, data_length)| (name, (rows, data_length)))
+            .collect();
     }
     
-    pub fn set_tables(&mut self, tables: Vec<String>) {
+    pub fn set_tables(&mut self, mut tables: Vec<String>) {
+        pin_favorites(&mut tables, &self.favorite_tables);
+        let previous = self.get_selected_table().cloned();
         self.tables = tables;
-        if !self.tables.is_empty() && self.table_list_state.selected().is_none() {
-            self.table_list_state.select(Some(0));
+        self.refresh_list_filter();
+        if let Some(position) = self.kept_selection(ViewMode::Tables, &self.tables, previous, self.table_list_state.selected()) {
+            self.table_list_state.select(Some(position));
         }
     }
     
-    pub fn set_table_data(&mut self, columns: Vec<String>, rows: Vec<Vec<String>>) {
+    pub fn set_table_data(&mut self, columns: Vec<String>, keys: Vec<ColumnKey>, rows: Vec<DataRow>, binary_cells: BinaryCells) {
+        // Column labels are "name (type)", as built by get_table_data
+        self.table_numeric_columns = columns
+            .iter()
+            .map(|col| col.split_once(" (").is_some_and(|(_, type_info)| database::is_numeric_type(type_info)))
+            .collect();
+        self.table_json_columns = columns
+            .iter()
+            .map(|col| col.split_once(" (").is_some_and(|(_, type_info)| database::is_json_type(type_info.trim_end_matches(')'))))
+            .collect();
         self.table_columns = columns;
+        self.table_column_keys = keys;
         self.table_rows = rows;
+        self.binary_cells = binary_cells;
+        // A restored hidden set may cover the column under the cursor
+        self.keep_columns_in_view();
         if !self.table_rows.is_empty() && self.data_table_state.selected().is_none() {
             self.data_table_state.select(Some(0));
         }
+        self.update_search_matches();
+    }
+    
+    // Whether a cell value matches the search term
+    pub fn matches_search(&self, value: &str) -> bool {
+        self.search_pattern.as_ref().is_some_and(|pattern| pattern.matches(value))
+    }
+    
+    // An invalid regex keeps the previous search and returns the error
+    pub fn set_search(&mut self, term: String, case_sensitive: bool, regex: bool) -> Result<(), String> {
+        let pattern = if term.is_empty() {
+            None
+        } else {
+            Some(SearchPattern::new(&term, case_sensitive, regex)?)
+        };
+        self.search_pattern = pattern;
+        self.search_term = (!term.is_empty()).then_some(term);
+        self.search_case_sensitive = case_sensitive;
+        self.search_regex = regex;
+        self.update_search_matches();
+        Ok(())
+    }
+    
+    fn update_search_matches(&mut self) {
+        self.search_matches = self
+            .table_rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| row.iter().flatten().any(|value| self.matches_search(value)))
+            .map(|(i, _)| i)
+            .collect();
+    }
+    
+    // Select the next (or previous) matching row after the current one, wrapping at the ends.
+    // Returns the 1-based match number and the match count.
+    pub fn jump_to_search_match(&mut self, forward: bool, include_current: bool) -> Option<(usize, usize)> {
+        if self.search_matches.is_empty() {
+            return None;
+        }
+        let current = self.data_table_state.selected().unwrap_or(0);
+        let position = if forward {
+            self.search_matches
+                .iter()
+                .position(|&row| row > current || (include_current && row == current))
+                .unwrap_or(0)
+        } else {
+            self.search_matches
+                .iter()
+                .rposition(|&row| row < current)
+                .unwrap_or(self.search_matches.len() - 1)
+        };
+        self.data_table_state.select(Some(self.search_matches[position]));
+        Some((position + 1, self.search_matches.len()))
+    }
+    
+    pub fn set_affected_rows_preview(&mut self, preview: SqlResult) {
+        self.affected_rows_preview = Some(preview);
+    }
+    
+    pub fn clear_affected_rows_preview(&mut self) {
+        self.affected_rows_preview = None;
+    }
+    
+    pub fn set_binlog_status(&mut self, status: Option<BinlogStatus>) {
+        self.binlog_status = status;
+    }
+    
+    pub fn set_replication_status(&mut self, status: Option<ReplicationStatus>) {
+        self.replication_status = status;
+    }
+    
+    // Keep the cursor on the same thread across refreshes while it still exists
+    pub fn set_process_list(&mut self, processes: Vec<ProcessInfo>) {
+        let selected_id = self.selected_process().map(|process| process.id);
+        let index = selected_id
+            .and_then(|id| processes.iter().position(|process| process.id == id))
+            .or_else(|| self.process_list_state.selected().map(|i| i.min(processes.len().saturating_sub(1))))
+            .unwrap_or(0);
+        self.process_list = processes;
+        self.process_list_state.select((!self.process_list.is_empty()).then_some(index));
+    }
+    
+    pub fn selected_process(&self) -> Option<&ProcessInfo> {
+        self.process_list_state.selected().and_then(|i| self.process_list.get(i))
     }
     
     pub fn get_selected_database(&self) -> Option<&String> {
         self.database_list_state
             .selected()
+            .and_then(|i| self.list_index(ViewMode::Databases, i))
             .and_then(|i| self.databases.get(i))
     }
     
     pub fn get_selected_table(&self) -> Option<&String> {
         self.table_list_state
             .selected()
+            .and_then(|i| self.list_index(ViewMode::Tables, i))
             .and_then(|i| self.tables.get(i))
     }
     
@@ -226,22 +1200,41 @@ impl NavigationState {
         self.sql_input.pop();
     }
     
+    // The input stays in the editor until the statement succeeds, so a typo
+    // doesn't cost the draft (see clear_executed_input)
     pub fn execute_sql(&mut self) -> String {
         if !self.sql_input.trim().is_empty() {
             let sql = self.sql_input.trim().to_string();
             self.sql_history.push(sql.clone());
             self.sql_history_index = None;
-            self.sql_input.clear();
+            self.sql_history_stash = None;
+            self.last_executed_sql = Some(sql.clone());
             return sql;
         }
         String::new()
     }
     
+    // Clear the input after the statement it holds ran successfully; anything
+    // typed meanwhile is kept
+    pub fn clear_executed_input(&mut self) {
+        if self.last_executed_sql.as_deref() == Some(self.sql_input.trim()) {
+            self.clear_sql_input();
+        }
+    }
+    
+    pub fn clear_sql_input(&mut self) {
+        self.sql_input.clear();
+        self.sql_history_index = None;
+        self.sql_history_stash = None;
+        self.completion = None;
+    }
+    
     pub fn navigate_history_up(&mut self) {
         if !self.sql_history.is_empty() {
             match self.sql_history_index {
                 None => {
                     self.sql_history_index = Some(self.sql_history.len() - 1);
+                    self.sql_history_stash = Some(std::mem::take(&mut self.sql_input));
                     self.sql_input = self.sql_history[self.sql_history.len() - 1].clone();
                 },
                 Some(index) if index > 0 => {
@@ -260,55 +1253,692 @@ impl NavigationState {
                 self.sql_input = self.sql_history[index + 1].clone();
             } else {
                 self.sql_history_index = None;
-                self.sql_input.clear();
+                self.sql_input = self.sql_history_stash.take().unwrap_or_default();
             }
         }
     }
     
     pub fn set_sql_result(&mut self, result: SqlResult) {
         self.sql_result = Some(result);
+        self.sql_error_scroll = 0;
+        self.explain_comparison = None;
+        // A single execution replaces any tabbed results from a queue run
+        self.result_tabs.clear();
+        self.active_result_tab = 0;
     }
     
     pub fn clear_sql_result(&mut self) {
         self.sql_result = None;
+        self.sql_error_scroll = 0;
+        self.explain_comparison = None;
+        self.result_tabs.clear();
+        self.active_result_tab = 0;
+    }
+    
+    pub fn scroll_sql_error(&mut self, lines: i32) {
+        self.sql_error_scroll = (self.sql_error_scroll as i32 + lines).max(0) as u16;
+    }
+    
+    pub fn set_explain_comparison(&mut self, comparison: ExplainComparison) {
+        self.sql_result = None;
+        self.result_tabs.clear();
+        self.active_result_tab = 0;
+        self.explain_comparison = Some(comparison);
+    }
+    
+    pub fn queue_sql_input(&mut self) -> Option<String> {
+        let sql = self.sql_input.trim().to_string();
+        if sql.is_empty() {
+            return None;
+        }
+        self.query_queue.push(sql.clone());
+        self.sql_history_index = None;
+        self.sql_input.clear();
+        Some(sql)
+    }
+    
+    pub fn take_query_queue(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.query_queue)
+    }
+    
+    pub fn clear_query_queue(&mut self) {
+        self.query_queue.clear();
+    }
+    
+    pub fn set_result_tabs(&mut self, tabs: Vec<ResultTab>) {
+        self.sql_result = None;
+        self.explain_comparison = None;
+        self.result_tabs = tabs;
+        self.active_result_tab = 0;
+        self.sql_error_scroll = 0;
+    }
+    
+    pub fn next_result_tab(&mut self) {
+        if !self.result_tabs.is_empty() {
+            self.active_result_tab = (self.active_result_tab + 1) % self.result_tabs.len();
+            self.sql_error_scroll = 0;
+        }
+    }
+    
+    pub fn prev_result_tab(&mut self) {
+        if !self.result_tabs.is_empty() {
+            self.sql_error_scroll = 0;
+            self.active_result_tab = if self.active_result_tab == 0 {
+                self.result_tabs.len() - 1
+            } else {
+                self.active_result_tab - 1
+            };
+        }
+    }
+    
+    pub fn get_active_result_tab(&self) -> Option<&ResultTab> {
+        self.result_tabs.get(self.active_result_tab)
+    }
+    
+    // Replace the partial word at the end of the input with the chosen candidate
+    pub fn insert_completion(&mut self, word_len: usize, candidate: &str) {
+        let keep = self.sql_input.len() - word_len;
+        self.sql_input.truncate(keep);
+        self.sql_input.push_str(candidate);
+        self.completion = None;
+    }
+    
+    pub fn accept_completion(&mut self) {
+        if let Some(completion) = self.completion.take() {
+            self.insert_completion(completion.word_len, completion.selected_candidate());
+        }
     }
     
     pub fn set_sql_history(&mut self, history: Vec<String>) {
         self.sql_history = history;
     }
     
+    // The last few executed queries, most recent first
+    pub fn recent_queries(&self) -> Vec<&String> {
+        self.sql_history.iter().rev().take(RECENT_QUERIES_SHOWN).collect()
+    }
+    
+    pub fn open_recent_queries(&mut self) -> bool {
+        if self.sql_history.is_empty() {
+            return false;
+        }
+        self.recent_queries_selected = Some(0);
+        true
+    }
+    
+    pub fn close_recent_queries(&mut self) {
+        self.recent_queries_selected = None;
+    }
+    
+    pub fn move_recent_queries_selection(&mut self, down: bool) {
+        let count = self.recent_queries().len();
+        if let Some(selected) = self.recent_queries_selected {
+            self.recent_queries_selected = Some(if down {
+                (selected + 1).min(count.saturating_sub(1))
+            } else {
+                selected.saturating_sub(1)
+            });
+        }
+    }
+    
+    // Put the chosen recent query in the editor input and close the list
+    pub fn load_recent_query(&mut self, index: usize) -> Option<String> {
+        let sql = self.recent_queries().get(index).map(|sql| sql.to_string())?;
+        self.sql_input = sql.clone();
+        self.sql_history_index = None;
+        self.recent_queries_selected = None;
+        Some(sql)
+    }
+    
+    // Record the table size and keep the offset within it; an offset past the end
+    // lands on the last page instead of showing nothing
+    pub fn set_table_total_rows(&mut self, total: u64) {
+        self.table_total_rows = Some(total);
+        self.page_offset = self.page_offset.min(last_page_offset(total, self.row_limit));
+    }
+    
+    pub fn next_page(&mut self) -> bool {
+        let Some(limit) = self.row_limit else {
+            return false;
+        };
+        let next = self.page_offset + limit;
+        match self.table_total_rows {
+            Some(total) if next as u64 >= total => false,
+            _ => {
+                self.page_offset = next;
+                self.data_table_state.select(Some(0));
+                true
+            }
+        }
+    }
+    
+    pub fn prev_page(&mut self) -> bool {
+        if self.page_offset == 0 {
+            return false;
+        }
+        self.page_offset = self.page_offset.saturating_sub(self.row_limit.unwrap_or(self.page_offset));
+        self.data_table_state.select(Some(0));
+        true
+    }
+    
+    // "rows 101-200 of 1234" for the current page
+    pub fn page_description(&self) -> String {
+        let start = self.page_offset + 1;
+        let end = self.page_offset + self.table_rows.len();
+        match self.table_total_rows {
+            Some(total) if self.table_rows.is_empty() => format!("rows 0 of {}", total),
+            Some(total) => format!("rows {}–{} of {}", start, end, total),
+            None => format!("rows {}–{}", start, end),
+        }
+    }
+    
+    // Multiply or divide the row limit by 10; dividing "no limit" starts from MAX_ROW_LIMIT
+    pub fn scale_row_limit(&mut self, increase: bool) -> Option<usize> {
+        let limit = match self.row_limit {
+            Some(limit) if increase => limit.saturating_mul(10),
+            Some(limit) => limit / 10,
+            None if increase => return None,
+            None => MAX_ROW_LIMIT,
+        };
+        self.set_row_limit(Some(limit))
+    }
+    
+    // Double or halve the row limit
+    pub fn bump_row_limit(&mut self, increase: bool) -> Option<usize> {
+        let limit = match self.row_limit {
+            Some(limit) if increase => limit.saturating_mul(2),
+            Some(limit) => limit / 2,
+            None if increase => return None,
+            None => MAX_ROW_LIMIT,
+        };
+        self.set_row_limit(Some(limit))
+    }
+    
+    // Set the row limit, clamped to 1..=MAX_ROW_LIMIT
+    pub fn set_row_limit(&mut self, limit: Option<usize>) -> Option<usize> {
+        self.row_limit = limit.map(|limit| limit.clamp(1, MAX_ROW_LIMIT));
+        self.row_limit
+    }
+    
     pub fn toggle_expanded_columns(&mut self) {
         self.expanded_columns = !self.expanded_columns;
         // Reset horizontal scroll when toggling
         self.horizontal_scroll = 0;
+        if self.expanded_columns && self.focused_column.is_none() {
+            self.focused_column = self.shown_columns().first().copied();
+        }
+    }
+    
+    // Indices into table_columns of the columns not hidden with the column picker;
+    // all of them if a saved hidden set no longer leaves any
+    pub fn shown_columns(&self) -> Vec<usize> {
+        let shown: Vec<usize> = self
+            .table_column_names()
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| !self.hidden_columns.contains(*name))
+            .map(|(i, _)| i)
+            .collect();
+        if shown.is_empty() {
+            (0..self.table_columns.len()).collect()
+        } else {
+            shown
+        }
+    }
+    
+    // Hide or show a column; the last shown one stays. Returns the column name and
+    // whether it is hidden now.
+    pub fn toggle_column_hidden(&mut self, index: usize) -> Option<(String, bool)> {
+        let name = self.table_column_names().into_iter().nth(index)?;
+        let hidden = if self.hidden_columns.remove(&name) {
+            false
+        } else if self.shown_columns().len() > 1 {
+            self.hidden_columns.insert(name.clone())
+        } else {
+            return None;
+        };
+        self.keep_columns_in_view();
+        Some((name, hidden))
     }
     
-    pub fn scroll_right(&mut self) {
-        if self.expanded_columns && !self.table_columns.is_empty() {
-            let max_scroll = self.table_columns.len().saturating_sub(self.visible_columns);
-            if self.horizontal_scroll < max_scroll {
-                self.horizontal_scroll += 1;
+    pub fn show_all_columns(&mut self) {
+        self.hidden_columns.clear();
+        self.keep_columns_in_view();
+    }
+    
+    // Move the column cursor off a hidden column and keep the scroll within the shown ones
+    fn keep_columns_in_view(&mut self) {
+        let shown = self.shown_columns();
+        if let Some(focused) = self.focused_column.filter(|focused| !shown.contains(focused)) {
+            self.focused_column = shown.iter().copied().find(|&i| i > focused).or(shown.last().copied());
+        }
+        self.horizontal_scroll = self.horizontal_scroll.min(shown.len().saturating_sub(self.column_window()));
+        if let Some(position) = self.focused_column.and_then(|focused| shown.iter().position(|&i| i == focused)) {
+            self.ensure_column_visible(position);
+        }
+    }
+    
+    // The column the cell actions work on: the cursor, else the first shown column
+    fn cursor_column(&self) -> usize {
+        self.focused_column.or_else(|| self.shown_columns().first().copied()).unwrap_or(0)
+    }
+    
+    // Widths of the visible columns in expanded mode: manual widths as set, the
+    // remaining space shared evenly by the others (at least EXPANDED_COLUMN_WIDTH)
+    pub fn expanded_column_widths(&self, available_width: u16) -> Vec<u16> {
+        let names = self.table_column_names();
+        let manual: Vec<Option<u16>> = self
+            .visible_column_indices()
+            .into_iter()
+            .map(|i| self.column_widths.get(&names[i]).copied())
+            .collect();
+        
+        let pinned: u16 = manual.iter().flatten().sum();
+        let unpinned = manual.iter().filter(|width| width.is_none()).count().max(1) as u16;
+        let shared = (available_width.saturating_sub(pinned) / unpinned).max(EXPANDED_COLUMN_WIDTH);
+        manual.into_iter().map(|width| width.unwrap_or(shared)).collect()
+    }
+    
+    // Grow or shrink the focused column by a step, starting from its current width.
+    // Returns the column name and its new width.
+    pub fn resize_focused_column(&mut self, grow: bool, available_width: u16) -> Option<(String, u16)> {
+        if !self.expanded_columns {
+            return None;
+        }
+        let column = self.focused_column?;
+        let position = self.visible_column_indices().iter().position(|&i| i == column)?;
+        let current = *self.expanded_column_widths(available_width).get(position)?;
+        let step = if grow { COLUMN_RESIZE_STEP } else { -COLUMN_RESIZE_STEP };
+        let width = (current as i32 + step).clamp(MIN_COLUMN_WIDTH as i32, available_width.max(MIN_COLUMN_WIDTH) as i32) as u16;
+        let name = self.focused_column_name()?;
+        self.column_widths.insert(name.clone(), width);
+        Some((name, width))
+    }
+    
+    // Number of columns shown at once in the current layout
+    fn column_window(&self) -> usize {
+        if self.expanded_columns {
+            self.visible_columns
+        } else {
+            self.compact_columns
+        }
+    }
+    
+    // Whether compact mode can't fit every column and scrolls horizontally
+    pub fn compact_columns_overflow(&self) -> bool {
+        !self.expanded_columns && self.shown_columns().len() > self.compact_columns
+    }
+    
+    pub fn fit_compact_columns(&mut self, terminal_width: u16) {
+        let available = terminal_width.saturating_sub(4); // Account for borders
+        self.compact_columns = (available / COMPACT_COLUMN_WIDTH).max(1) as usize;
+        if !self.expanded_columns {
+            let max_scroll = self.shown_columns().len().saturating_sub(self.compact_columns);
+            self.horizontal_scroll = self.horizontal_scroll.min(max_scroll);
+        }
+    }
+    
+    // Position of the column cursor among the shown columns, or the first one on screen
+    fn cursor_position(&self, shown: &[usize]) -> usize {
+        self.focused_column
+            .and_then(|focused| shown.iter().position(|&i| i == focused))
+            .unwrap_or(self.horizontal_scroll)
+    }
+    
+    // In expanded mode ←/→ move the column cursor and the view scrolls to keep it visible;
+    // in compact mode they shift the column window by one. Hidden columns are skipped.
+    // Returns whether anything moved.
+    pub fn scroll_right(&mut self) -> bool {
+        if self.table_columns.is_empty() {
+            return false;
+        }
+        let shown = self.shown_columns();
+        if self.expanded_columns {
+            let current = self.cursor_position(&shown);
+            let next = (current + 1).min(shown.len() - 1);
+            self.focused_column = Some(shown[next]);
+            self.ensure_column_visible(next);
+            next != current
+        } else {
+            let max_scroll = shown.len().saturating_sub(self.compact_columns);
+            let previous = self.horizontal_scroll;
+            self.horizontal_scroll = (previous + 1).min(max_scroll);
+            self.horizontal_scroll != previous
+        }
+    }
+    
+    pub fn scroll_left(&mut self) -> bool {
+        if self.table_columns.is_empty() {
+            return false;
+        }
+        if self.expanded_columns {
+            let shown = self.shown_columns();
+            let current = self.cursor_position(&shown);
+            let next = current.saturating_sub(1);
+            self.focused_column = Some(shown[next]);
+            self.ensure_column_visible(next);
+            next != current
+        } else {
+            let previous = self.horizontal_scroll;
+            self.horizontal_scroll = previous.saturating_sub(1);
+            self.horizontal_scroll != previous
+        }
+    }
+    
+    // Scroll so the shown column at `position` is on screen
+    fn ensure_column_visible(&mut self, position: usize) {
+        let window = self.column_window();
+        if position < self.horizontal_scroll {
+            self.horizontal_scroll = position;
+        } else if position >= self.horizontal_scroll + window {
+            self.horizontal_scroll = position + 1 - window;
+        }
+    }
+    
+    // Open the full-value popup for the selected row and column (first column without a cursor)
+    pub fn open_cell_detail(&mut self) -> bool {
+        let row = self.data_table_state.selected().and_then(|i| self.table_rows.get(i));
+        let column = self.cursor_column();
+        match (row.and_then(|row| row.get(column)), self.table_columns.get(column)) {
+            (Some(value), Some(label)) => {
+                let name = label.split(" (").next().unwrap_or(label).to_string();
+                self.cell_detail = Some((name, value.clone()));
+                self.cell_detail_scroll = 0;
+                self.cell_detail_bytes = self
+                    .data_table_state
+                    .selected()
+                    .and_then(|row| self.binary_cells.get(&(row, column)))
+                    .cloned();
+                self.cell_detail_json = self.table_json_columns.get(column).copied().unwrap_or(false);
+                true
+            },
+            _ => false,
+        }
+    }
+    
+    pub fn close_cell_detail(&mut self) {
+        self.cell_detail = None;
+        self.cell_detail_scroll = 0;
+        self.cell_detail_hscroll = 0;
+        self.cell_detail_bytes = None;
+        self.cell_detail_json = false;
+    }
+    
+    pub fn scroll_cell_detail(&mut self, lines: i32) {
+        self.cell_detail_scroll = (self.cell_detail_scroll as i32 + lines).max(0) as u16;
+    }
+    
+    pub fn scroll_cell_detail_horizontal(&mut self, columns: i32) {
+        if !self.cell_detail_wrap {
+            self.cell_detail_hscroll = (self.cell_detail_hscroll as i32 + columns).max(0) as u16;
+        }
+    }
+    
+    pub fn open_ddl_popup(&mut self, table: String, ddl: String) {
+        self.ddl_popup = Some((table, ddl));
+        self.ddl_popup_scroll = 0;
+    }
+    
+    pub fn close_ddl_popup(&mut self) {
+        self.ddl_popup = None;
+        self.ddl_popup_scroll = 0;
+    }
+    
+    pub fn scroll_ddl_popup(&mut self, lines: i32) {
+        self.ddl_popup_scroll = (self.ddl_popup_scroll as i32 + lines).max(0) as u16;
+    }
+    
+    pub fn open_schema_popup(&mut self, popup: SchemaPopup) {
+        self.schema_popup = Some(popup);
+    }
+    
+    pub fn close_schema_popup(&mut self) {
+        self.schema_popup = None;
+    }
+    
+    // Bare name (without the "(type)" suffix) of the column under the cursor
+    // Column names without the " (type)" suffix of the labels
+    pub fn table_column_names(&self) -> Vec<String> {
+        self.table_columns
+            .iter()
+            .map(|col| col.split(" (").next().unwrap_or(col).to_string())
+            .collect()
+    }
+    
+    pub fn remove_table_row(&mut self, index: usize) {
+        if index < self.table_rows.len() {
+            self.table_rows.remove(index);
+            // Binary cells of the rows below move up with them
+            self.binary_cells = std::mem::take(&mut self.binary_cells)
+                .into_iter()
+                .filter(|((row, _), _)| *row != index)
+                .map(|((row, column), bytes)| ((if row > index { row - 1 } else { row }, column), bytes))
+                .collect();
+            if let Some(total) = &mut self.table_total_rows {
+                *total = total.saturating_sub(1);
             }
+            let last = self.table_rows.len().checked_sub(1);
+            self.data_table_state.select(last.map(|last| index.min(last)));
+            self.update_search_matches();
         }
     }
     
-    pub fn scroll_left(&mut self) {
-        if self.expanded_columns && self.horizontal_scroll > 0 {
-            self.horizontal_scroll -= 1;
+    // Row index, column index and value of the cell under the cursor
+    pub fn selected_cell(&self) -> Option<(usize, usize, Option<String>)> {
+        let row = self.data_table_state.selected()?;
+        let column = self.cursor_column();
+        let value = self.table_rows.get(row)?.get(column)?.clone();
+        Some((row, column, value))
+    }
+    
+    // "name: 142 chars / 156 bytes" for the selected cell, shown in the status bar
+    pub fn selected_cell_length(&self) -> Option<String> {
+        let (row, column, value) = self.selected_cell()?;
+        let label = self.table_columns.get(column)?;
+        let name = label.split(" (").next().unwrap_or(label);
+        let length = match (self.binary_cells.get(&(row, column)), value) {
+            (Some(bytes), _) => format!("{} bytes (binary)", bytes.len()),
+            (None, Some(value)) => format!("{} chars / {} bytes", value.chars().count(), value.len()),
+            (None, None) => "NULL".to_string(),
+        };
+        Some(format!("{}: {}", name, length))
+    }
+    
+    pub fn focused_column_name(&self) -> Option<String> {
+        self.focused_column
+            .and_then(|i| self.table_columns.get(i))
+            .map(|col| col.split(" (").next().unwrap_or(col).to_string())
+    }
+    
+    // Sort by the focused column: ascending first, then toggle the direction
+    pub fn toggle_sort_on_focused_column(&mut self) -> Option<(String, bool)> {
+        let column = self.focused_column_name()?;
+        if self.sort_column.as_deref() == Some(column.as_str()) {
+            self.sort_desc = !self.sort_desc;
+        } else {
+            self.sort_column = Some(column.clone());
+            self.sort_desc = false;
         }
+        self.page_offset = 0;
+        Some((column, self.sort_desc))
     }
     
+    // Sort by a column given by index, e.g. a clicked header; toggles like the focused column
+    pub fn toggle_sort_on_column(&mut self, index: usize) -> Option<(String, bool)> {
+        if index >= self.table_columns.len() {
+            return None;
+        }
+        self.focused_column = Some(index);
+        self.toggle_sort_on_focused_column()
+    }
+    
+    // Sort by a column given by name (case-insensitive); returns its exact name
+    pub fn set_sort(&mut self, column: &str, desc: bool) -> Option<String> {
+        let column = self
+            .table_column_names()
+            .into_iter()
+            .find(|name| name.eq_ignore_ascii_case(column))?;
+        self.sort_column = Some(column.clone());
+        self.sort_desc = desc;
+        self.page_offset = 0;
+        Some(column)
+    }
+    
+    pub fn sort_order(&self) -> Option<(String, bool)> {
+        self.sort_column.clone().map(|column| (column, self.sort_desc))
+    }
+    
+    // Range of positions among the shown columns that is on screen
     pub fn get_visible_columns(&self) -> (usize, usize) {
-        if !self.expanded_columns || self.table_columns.is_empty() {
-            return (0, self.table_columns.len());
+        let shown = self.shown_columns().len();
+        if shown == 0 {
+            return (0, 0);
         }
         
-        let start = self.horizontal_scroll;
-        let end = (start + self.visible_columns).min(self.table_columns.len());
+        let start = self.horizontal_scroll.min(shown - 1);
+        let end = start.saturating_add(self.column_window()).min(shown);
         (start, end)
     }
     
+    // Indices into table_columns of the columns on screen
+    pub fn visible_column_indices(&self) -> Vec<usize> {
+        let (start, end) = self.get_visible_columns();
+        self.shown_columns()[start..end].to_vec()
+    }
+    
     pub fn set_visible_columns(&mut self, count: usize) {
         self.visible_columns = count.max(1); // At least 1 column visible
     }
+    
+    // Jump to the best match among the shown columns
+    pub fn scroll_to_column(&mut self, name: &str) -> bool {
+        let shown = self.shown_columns();
+        let best = shown
+            .iter()
+            .enumerate()
+            .filter_map(|(position, &i)| {
+                // Match against the bare column name, not the "(type)" suffix
+                let col = &self.table_columns[i];
+                let col_name = col.split(" (").next().unwrap_or(col);
+                match_score(col_name, name).map(|score| (score, position))
+            })
+            .min();
+        
+        match best {
+            Some((_, position)) => {
+                self.focused_column = Some(shown[position]);
+                let max_scroll = shown.len().saturating_sub(self.column_window());
+                self.horizontal_scroll = position.min(max_scroll);
+                true
+            },
+            None => false,
+        }
+    }
+    
+    pub fn open_prompt(&mut self, kind: PromptKind, input: String) {
+        self.input_prompt = Some(InputPrompt { kind, input });
+    }
+    
+    pub fn toggle_prompt_option(&mut self) {
+        match &mut self.input_prompt {
+            Some(InputPrompt { kind: PromptKind::DumpTableData { include_ddl, .. }, .. }) => *include_ddl = !*include_ddl,
+            Some(InputPrompt { kind: PromptKind::SetRowLimit { save_as_default }, .. }) => *save_as_default = !*save_as_default,
+            Some(InputPrompt { kind: PromptKind::Search { case_sensitive, .. }, .. }) => *case_sensitive = !*case_sensitive,
+            Some(InputPrompt { kind: PromptKind::EditCell { set_null, .. }, .. }) => *set_null = !*set_null,
+            Some(InputPrompt { kind: PromptKind::QueryParam { set_null, .. }, .. }) => *set_null = !*set_null,
+            _ => {}
+        }
+    }
+    
+    pub fn toggle_prompt_regex(&mut self) {
+        if let Some(InputPrompt { kind: PromptKind::Search { regex, .. }, .. }) = &mut self.input_prompt {
+            *regex = !*regex;
+        }
+    }
+    
+    pub fn close_prompt(&mut self) -> Option<InputPrompt> {
+        self.input_prompt.take()
+    }
+    
+    pub fn add_to_prompt(&mut self, ch: char) {
+        if let Some(prompt) = &mut self.input_prompt {
+            prompt.input.push(ch);
+        }
+    }
+    
+    pub fn backspace_prompt(&mut self) {
+        if let Some(prompt) = &mut self.input_prompt {
+            prompt.input.pop();
+        }
+    }
+}
+
+// Lower scores are better matches; None means the query doesn't match at all
+fn match_score(column: &str, query: &str) -> Option<usize> {
+    let column = column.to_lowercase();
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+    
+    if column == query {
+        return Some(0);
+    }
+    if column.starts_with(&query) {
+        return Some(1);
+    }
+    if column.contains(&query) {
+        return Some(2);
+    }
+    
+    // Fuzzy: every query character appears in order, scored by how spread out they are
+    let mut chars = column.char_indices();
+    let mut first = None;
+    let mut last = 0;
+    for q in query.chars() {
+        let (pos, _) = chars.find(|(_, c)| *c == q)?;
+        first.get_or_insert(pos);
+        last = pos;
+    }
+    Some(3 + last - first.unwrap_or(0))
+}
+
+// Offset of the last page of `total` rows; 0 when there is no row limit
+pub fn last_page_offset(total: u64, row_limit: Option<usize>) -> usize {
+    match row_limit {
+        Some(limit) if total > 0 => (total as usize - 1) / limit * limit,
+        _ => 0,
+    }
+}
+
+// Character positions of `name` matched by `query`, for highlighting: the
+// substring match when there is one, otherwise the in-order fuzzy match
+pub fn match_positions(name: &str, query: &str) -> Vec<usize> {
+    let lower: Vec<char> = name.to_lowercase().chars().collect();
+    let query: Vec<char> = query.trim().to_lowercase().chars().collect();
+    if query.is_empty() || lower.len() != name.chars().count() {
+        return Vec::new();
+    }
+    
+    if let Some(start) = lower.windows(query.len()).position(|window| window == query.as_slice()) {
+        return (start..start + query.len()).collect();
+    }
+    
+    let mut positions = Vec::new();
+    let mut from = 0;
+    for q in query {
+        match lower[from..].iter().position(|c| *c == q) {
+            Some(offset) => {
+                positions.push(from + offset);
+                from += offset + 1;
+            },
+            None => return Vec::new(),
+        }
+    }
+    positions
+}
+
+// Move favorites to the front, keeping the order within both groups
+fn pin_favorites(items: &mut [String], favorites: &HashSet<String>) {
+    items.sort_by_key(|item| !favorites.contains(item));
 }
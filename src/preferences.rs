@@ -0,0 +1,229 @@
+// In-app editor for UserPreferences, shown as a popup over the main views
+
+use crate::navigation::MAX_SQL_INPUT_LINES;
+use crate::theme::BUILTIN_THEMES;
+use crate::ui::{MAX_CELL_DISPLAY_CHARS, MIN_CELL_DISPLAY_CHARS};
+use crate::user_config::UserPreferences;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PreferenceField {
+    AutoSaveHistory,
+    MaxHistoryEntries,
+    ShowExecutionTime,
+    DefaultLimit,
+    ShowAffectedRowsPreview,
+    IdleLockMinutes,
+    ShowLocalTime,
+    QueryTimeoutSecs,
+    Theme,
+    SaveExplainHistory,
+    StopScriptOnError,
+    OrderByPrimaryKey,
+    MaxResultRows,
+    SqlInputLines,
+    ShowBlankMarkers,
+    AutoRefreshSecs,
+    CellDisplayChars,
+    RelativeNumbers,
+}
+
+pub const PREFERENCE_FIELDS: [PreferenceField; 18] = [
+    PreferenceField::AutoSaveHistory,
+    PreferenceField::MaxHistoryEntries,
+    PreferenceField::ShowExecutionTime,
+    PreferenceField::DefaultLimit,
+    PreferenceField::ShowAffectedRowsPreview,
+    PreferenceField::IdleLockMinutes,
+    PreferenceField::ShowLocalTime,
+    PreferenceField::QueryTimeoutSecs,
+    PreferenceField::Theme,
+    PreferenceField::SaveExplainHistory,
+    PreferenceField::StopScriptOnError,
+    PreferenceField::OrderByPrimaryKey,
+    PreferenceField::MaxResultRows,
+    PreferenceField::SqlInputLines,
+    PreferenceField::ShowBlankMarkers,
+    PreferenceField::AutoRefreshSecs,
+    PreferenceField::CellDisplayChars,
+    PreferenceField::RelativeNumbers,
+];
+
+impl PreferenceField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PreferenceField::AutoSaveHistory => "Save SQL history",
+            PreferenceField::MaxHistoryEntries => "Max history entries",
+            PreferenceField::ShowExecutionTime => "Show execution time",
+            PreferenceField::DefaultLimit => "Default row limit",
+            PreferenceField::ShowAffectedRowsPreview => "Preview affected rows",
+            PreferenceField::IdleLockMinutes => "Idle lock (minutes)",
+            PreferenceField::ShowLocalTime => "Timestamps in local time",
+            PreferenceField::QueryTimeoutSecs => "Query timeout (seconds)",
+            PreferenceField::Theme => "Theme",
+            PreferenceField::SaveExplainHistory => "Save EXPLAIN runs in history",
+            PreferenceField::StopScriptOnError => "Stop scripts at first error",
+            PreferenceField::OrderByPrimaryKey => "Order table data by key",
+            PreferenceField::MaxResultRows => "Max rows kept per result",
+            PreferenceField::SqlInputLines => "SQL input height (lines)",
+            PreferenceField::ShowBlankMarkers => "Mark empty/blank strings",
+            PreferenceField::AutoRefreshSecs => "Auto-refresh every (seconds)",
+            PreferenceField::CellDisplayChars => "Cell display length (chars)",
+            PreferenceField::RelativeNumbers => "Relative row numbers",
+        }
+    }
+
+    pub fn is_toggle(&self) -> bool {
+        matches!(
+            self,
+            PreferenceField::AutoSaveHistory
+                | PreferenceField::ShowExecutionTime
+                | PreferenceField::ShowAffectedRowsPreview
+                | PreferenceField::ShowLocalTime
+                | PreferenceField::Theme
+                | PreferenceField::SaveExplainHistory
+                | PreferenceField::StopScriptOnError
+                | PreferenceField::OrderByPrimaryKey
+                | PreferenceField::ShowBlankMarkers
+                | PreferenceField::RelativeNumbers
+        )
+    }
+
+    // Numeric fields that can be left empty
+    fn is_optional(&self) -> bool {
+        matches!(self, PreferenceField::DefaultLimit | PreferenceField::IdleLockMinutes)
+    }
+
+    pub fn value(&self, prefs: &UserPreferences) -> String {
+        let flag = |on: bool| if on { "on" } else { "off" }.to_string();
+        match self {
+            PreferenceField::AutoSaveHistory => flag(prefs.auto_save_history),
+            PreferenceField::MaxHistoryEntries => prefs.max_history_entries.to_string(),
+            PreferenceField::ShowExecutionTime => flag(prefs.show_execution_time),
+            PreferenceField::DefaultLimit => prefs.default_limit.map_or("none".to_string(), |limit| limit.to_string()),
+            PreferenceField::ShowAffectedRowsPreview => flag(prefs.show_affected_rows_preview),
+            PreferenceField::IdleLockMinutes => prefs.idle_lock_minutes.map_or("off".to_string(), |minutes| minutes.to_string()),
+            PreferenceField::ShowLocalTime => flag(prefs.show_local_time),
+            PreferenceField::QueryTimeoutSecs => match prefs.query_timeout_secs {
+                0 => "none".to_string(),
+                secs => secs.to_string(),
+            },
+            PreferenceField::Theme => prefs.theme.clone(),
+            PreferenceField::SaveExplainHistory => flag(prefs.save_explain_history),
+            PreferenceField::StopScriptOnError => flag(prefs.stop_script_on_error),
+            PreferenceField::OrderByPrimaryKey => flag(prefs.order_by_primary_key),
+            PreferenceField::MaxResultRows => match prefs.max_result_rows {
+                0 => "none".to_string(),
+                rows => rows.to_string(),
+            },
+            PreferenceField::SqlInputLines => prefs.sql_input_lines.to_string(),
+            PreferenceField::ShowBlankMarkers => flag(prefs.show_blank_markers),
+            PreferenceField::AutoRefreshSecs => prefs.auto_refresh_secs.to_string(),
+            PreferenceField::CellDisplayChars => prefs.cell_display_chars.to_string(),
+            PreferenceField::RelativeNumbers => flag(prefs.relative_numbers),
+        }
+    }
+
+    // The value as typed into the edit buffer
+    fn input(&self, prefs: &UserPreferences) -> String {
+        match self {
+            PreferenceField::DefaultLimit => prefs.default_limit.map(|limit| limit.to_string()).unwrap_or_default(),
+            PreferenceField::IdleLockMinutes => prefs.idle_lock_minutes.map(|minutes| minutes.to_string()).unwrap_or_default(),
+            _ => self.value(prefs),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PreferencesEditor {
+    // Working copy; only written to the config when saved
+    pub prefs: UserPreferences,
+    pub selected: usize,
+    // Input buffer while a numeric field is being edited
+    pub editing: Option<String>,
+}
+
+impl PreferencesEditor {
+    pub fn new(prefs: UserPreferences) -> Self {
+        PreferencesEditor {
+            prefs,
+            selected: 0,
+            editing: None,
+        }
+    }
+
+    pub fn selected_field(&self) -> PreferenceField {
+        PREFERENCE_FIELDS[self.selected]
+    }
+
+    pub fn move_selection(&mut self, down: bool) {
+        self.selected = if down {
+            (self.selected + 1).min(PREFERENCE_FIELDS.len() - 1)
+        } else {
+            self.selected.saturating_sub(1)
+        };
+    }
+
+    // Toggle a bool field, cycle the theme, or start editing a numeric one
+    pub fn activate(&mut self) {
+        let field = self.selected_field();
+        let prefs = &mut self.prefs;
+        match field {
+            PreferenceField::AutoSaveHistory => prefs.auto_save_history = !prefs.auto_save_history,
+            PreferenceField::ShowExecutionTime => prefs.show_execution_time = !prefs.show_execution_time,
+            PreferenceField::ShowAffectedRowsPreview => prefs.show_affected_rows_preview = !prefs.show_affected_rows_preview,
+            PreferenceField::ShowLocalTime => prefs.show_local_time = !prefs.show_local_time,
+            PreferenceField::SaveExplainHistory => prefs.save_explain_history = !prefs.save_explain_history,
+            PreferenceField::StopScriptOnError => prefs.stop_script_on_error = !prefs.stop_script_on_error,
+            PreferenceField::OrderByPrimaryKey => prefs.order_by_primary_key = !prefs.order_by_primary_key,
+            PreferenceField::ShowBlankMarkers => prefs.show_blank_markers = !prefs.show_blank_markers,
+            PreferenceField::RelativeNumbers => prefs.relative_numbers = !prefs.relative_numbers,
+            // Custom theme files are set in user_config.json; cycling goes back to the built-ins
+            PreferenceField::Theme => {
+                let next = BUILTIN_THEMES
+                    .iter()
+                    .position(|name| *name == prefs.theme)
+                    .map_or(0, |i| (i + 1) % BUILTIN_THEMES.len());
+                prefs.theme = BUILTIN_THEMES[next].to_string();
+            },
+            _ => self.editing = Some(field.input(prefs)),
+        }
+    }
+
+    // Apply the edit buffer to the selected numeric field
+    pub fn commit_edit(&mut self) -> Result<(), String> {
+        let Some(input) = self.editing.take() else {
+            return Ok(());
+        };
+        let field = self.selected_field();
+        let input = input.trim();
+        let number = if input.is_empty() && field.is_optional() {
+            None
+        } else {
+            match input.parse::<u64>() {
+                Ok(number) => Some(number),
+                Err(_) => {
+                    let hint = if field.is_optional() { ", or nothing to turn it off" } else { "" };
+                    return Err(format!("{} must be a whole number{}", field.label(), hint));
+                }
+            }
+        };
+
+        let prefs = &mut self.prefs;
+        match field {
+            PreferenceField::MaxHistoryEntries => prefs.max_history_entries = number.unwrap_or(0).max(1) as usize,
+            PreferenceField::DefaultLimit => prefs.default_limit = number.map(|limit| limit.max(1) as usize),
+            PreferenceField::IdleLockMinutes => prefs.idle_lock_minutes = number.filter(|&minutes| minutes > 0),
+            PreferenceField::QueryTimeoutSecs => prefs.query_timeout_secs = number.unwrap_or(0),
+            PreferenceField::MaxResultRows => prefs.max_result_rows = number.unwrap_or(0) as usize,
+            PreferenceField::AutoRefreshSecs => prefs.auto_refresh_secs = number.unwrap_or(1).max(1),
+            PreferenceField::SqlInputLines => {
+                prefs.sql_input_lines = number.unwrap_or(1).clamp(1, u64::from(MAX_SQL_INPUT_LINES)) as u16
+            },
+            PreferenceField::CellDisplayChars => {
+                prefs.cell_display_chars = (number.unwrap_or(0) as usize).clamp(MIN_CELL_DISPLAY_CHARS, MAX_CELL_DISPLAY_CHARS)
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+}
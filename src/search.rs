@@ -0,0 +1,45 @@
+// Matching text against a search term: plain substring by default, or a regex
+
+use regex::{Regex, RegexBuilder};
+
+#[derive(Debug, Clone)]
+pub enum SearchPattern {
+    // The term is kept lowercased when matching ignores case
+    Plain { term: String, case_sensitive: bool },
+    Regex(Regex),
+}
+
+impl SearchPattern {
+    // `\c` or `\C` anywhere in the term forces case-insensitive or case-sensitive
+    // matching, as in vim. An invalid regex is reported as the error text.
+    pub fn new(term: &str, case_sensitive: bool, regex: bool) -> Result<Self, String> {
+        let case_sensitive = if term.contains("\\C") {
+            true
+        } else if term.contains("\\c") {
+            false
+        } else {
+            case_sensitive
+        };
+        let term = term.replace("\\C", "").replace("\\c", "");
+        
+        if regex {
+            RegexBuilder::new(&term)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .map(SearchPattern::Regex)
+                .map_err(|e| format!("Invalid regex: {}", e.to_string().lines().last().unwrap_or_default()))
+        } else if case_sensitive {
+            Ok(SearchPattern::Plain { term, case_sensitive })
+        } else {
+            Ok(SearchPattern::Plain { term: term.to_lowercase(), case_sensitive })
+        }
+    }
+    
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            SearchPattern::Plain { term, case_sensitive: true } => value.contains(term.as_str()),
+            SearchPattern::Plain { term, case_sensitive: false } => value.to_lowercase().contains(term.as_str()),
+            SearchPattern::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
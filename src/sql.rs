@@ -0,0 +1,377 @@
+// Lightweight helpers for inspecting and rewriting SQL text
+
+// For a string literal, quoted identifier or comment starting at byte `i`, the
+// index of its last byte (the closing quote, the newline or the closing `/`)
+fn skip_literal_or_comment(bytes: &[u8], mut i: usize) -> Option<usize> {
+    let b = bytes[i];
+    match b {
+        b'\'' | b'"' | b'`' => {
+            // Skip to the closing quote, honoring backslash escapes and doubled quotes
+            i += 1;
+            while i < bytes.len() {
+                if bytes[i] == b'\\' && b != b'`' {
+                    i += 1;
+                } else if bytes[i] == b {
+                    if bytes.get(i + 1) == Some(&b) {
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+                i += 1;
+            }
+        },
+        b'-' if bytes.get(i + 1) == Some(&b'-') => {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+        },
+        b'#' => {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+        },
+        b'/' if bytes.get(i + 1) == Some(&b'*') => {
+            i += 2;
+            while i + 1 < bytes.len() && !(bytes[i] == b'*' && bytes[i + 1] == b'/') {
+                i += 1;
+            }
+            i += 1;
+        },
+        _ => return None,
+    }
+    Some(i)
+}
+
+// Byte offset of `keyword` as a standalone word (or of a punctuation token like ";")
+// at nesting depth 0, skipping string literals, quoted identifiers and comments
+fn find_top_level_keyword(sql: &str, keyword: &str, from: usize) -> Option<usize> {
+    let bytes = sql.as_bytes();
+    let keyword = keyword.as_bytes();
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    // Word boundaries only matter for keywords that start/end with a word character
+    let (word_start, word_end) = match (keyword.first(), keyword.last()) {
+        (Some(&first), Some(&last)) => (is_word(first), is_word(last)),
+        _ => return None,
+    };
+    
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(end) = skip_literal_or_comment(bytes, i) {
+            i = end + 1;
+            continue;
+        }
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => depth = depth.saturating_sub(1),
+            _ => {
+                if depth == 0
+                    && i >= from
+                    && bytes.len() - i >= keyword.len()
+                    && bytes[i..i + keyword.len()].eq_ignore_ascii_case(keyword)
+                    && (i == 0 || !word_start || !is_word(bytes[i - 1]))
+                    && (!word_end || bytes.get(i + keyword.len()).is_none_or(|next| !is_word(*next)))
+                {
+                    return Some(i);
+                }
+            },
+        }
+        i += 1;
+    }
+    None
+}
+
+// Split a script on `;` into its statements. Semicolons inside string literals,
+// quoted identifiers and comments don't count; comments before a statement and
+// parts holding nothing but comments are dropped. DELIMITER is not supported.
+pub fn split_statements(sql: &str) -> Vec<String> {
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+    // Start of the current statement's first token, once one was seen
+    let mut code_start = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if let Some(end) = skip_literal_or_comment(bytes, i) {
+            if matches!(b, b'\'' | b'"' | b'`') {
+                code_start.get_or_insert(i);
+            }
+            i = end;
+        } else if b == b';' {
+            if let Some(start) = code_start.take() {
+                statements.push(sql[start..i].trim_end().to_string());
+            }
+        } else if !b.is_ascii_whitespace() {
+            code_start.get_or_insert(i);
+        }
+        i += 1;
+    }
+    if let Some(start) = code_start {
+        statements.push(sql[start..].trim_end().to_string());
+    }
+    statements
+}
+
+// Rewrite `UPDATE tables SET ... [WHERE ...] [ORDER BY ...] [LIMIT ...]` into the
+// SELECT that returns the rows the UPDATE would touch
+pub fn update_to_select(sql: &str) -> Option<String> {
+    let sql = sql.trim().trim_end_matches(';').trim_end();
+    if find_top_level_keyword(sql, "UPDATE", 0) != Some(0) {
+        return None;
+    }
+    
+    let set_pos = find_top_level_keyword(sql, "SET", 0)?;
+    let mut tables = sql["UPDATE".len()..set_pos].trim();
+    for modifier in ["LOW_PRIORITY", "IGNORE"] {
+        if tables.len() > modifier.len() && tables[..modifier.len()].eq_ignore_ascii_case(modifier) {
+            tables = tables[modifier.len()..].trim_start();
+        }
+    }
+    if tables.is_empty() {
+        return None;
+    }
+    
+    // Everything from the first trailing clause on carries over unchanged
+    let after_set = set_pos + "SET".len();
+    let tail = ["WHERE", "ORDER", "LIMIT"]
+        .iter()
+        .filter_map(|keyword| find_top_level_keyword(sql, keyword, after_set))
+        .min()
+        .map(|pos| &sql[pos..])
+        .unwrap_or("");
+    
+    if tail.is_empty() {
+        Some(format!("SELECT * FROM {}", tables))
+    } else {
+        Some(format!("SELECT * FROM {} {}", tables, tail))
+    }
+}
+
+// For an UPDATE/DELETE without a top-level WHERE clause, the table it would
+// rewrite entirely; None for anything else
+pub fn unscoped_write_target(sql: &str) -> Option<String> {
+    let sql = sql.trim().trim_end_matches(';').trim_end();
+    let is_update = find_top_level_keyword(sql, "UPDATE", 0) == Some(0);
+    let is_delete = find_top_level_keyword(sql, "DELETE", 0) == Some(0);
+    if !is_update && !is_delete {
+        return None;
+    }
+    if find_top_level_keyword(sql, "WHERE", 0).is_some() {
+        return None;
+    }
+    
+    let target_start = if is_update {
+        "UPDATE".len()
+    } else {
+        find_top_level_keyword(sql, "FROM", 0).map(|pos| pos + "FROM".len()).unwrap_or("DELETE".len())
+    };
+    
+    let target = sql[target_start..]
+        .split_whitespace()
+        .find(|word| !["LOW_PRIORITY", "QUICK", "IGNORE"].iter().any(|m| word.eq_ignore_ascii_case(m)))
+        .map(|word| word.trim_matches('`').to_string())
+        .unwrap_or_default();
+    
+    if target.is_empty() {
+        Some(if is_update { "UPDATE".to_string() } else { "DELETE".to_string() })
+    } else {
+        Some(target)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransactionControl {
+    Begin,
+    Commit,
+    Rollback,
+}
+
+// BEGIN / START TRANSACTION / COMMIT / ROLLBACK typed on their own, which the
+// editor handles itself so the transaction stays on its pinned connection
+pub fn transaction_control(sql: &str) -> Option<TransactionControl> {
+    let sql = sql.trim().trim_end_matches(';').trim_end();
+    let words: Vec<String> = sql.split_whitespace().map(|word| word.to_uppercase()).collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    match words.as_slice() {
+        ["BEGIN"] | ["BEGIN", "WORK"] | ["START", "TRANSACTION"] => Some(TransactionControl::Begin),
+        ["COMMIT"] | ["COMMIT", "WORK"] => Some(TransactionControl::Commit),
+        ["ROLLBACK"] | ["ROLLBACK", "WORK"] => Some(TransactionControl::Rollback),
+        _ => None,
+    }
+}
+
+// Whether the statement has a /*! ... */ or /*M! ... */ comment outside
+// literals, whose content MySQL and MariaDB run as part of the statement
+fn has_executable_comment(sql: &str) -> bool {
+    let bytes = sql.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"/*!") || bytes[i..].starts_with(b"/*M!") {
+            return true;
+        }
+        if let Some(end) = skip_literal_or_comment(bytes, i) {
+            i = end;
+        }
+        i += 1;
+    }
+    false
+}
+
+// Whether read-only mode lets a statement through: a single SELECT, SHOW,
+// DESCRIBE or EXPLAIN that doesn't write through INTO (OUTFILE, DUMPFILE or
+// variables) and doesn't EXPLAIN a write (EXPLAIN ANALYZE runs it). Executable
+// comments are refused outright, since the checks below skip comments.
+pub fn is_read_only_statement(sql: &str) -> bool {
+    let sql = sql.trim().trim_end_matches(';').trim_end();
+    if has_executable_comment(sql) {
+        return false;
+    }
+    let Some(keyword) = ["SELECT", "SHOW", "DESCRIBE", "DESC", "EXPLAIN"]
+        .into_iter()
+        .find(|keyword| find_top_level_keyword(sql, keyword, 0) == Some(0))
+    else {
+        return false;
+    };
+    
+    if find_top_level_keyword(sql, ";", 0).is_some() || find_top_level_keyword(sql, "INTO", 0).is_some() {
+        return false;
+    }
+    keyword == "SELECT"
+        || keyword == "SHOW"
+        || !["INSERT", "UPDATE", "DELETE", "REPLACE"]
+            .iter()
+            .any(|write| find_top_level_keyword(sql, write, 0).is_some())
+}
+
+// Statements EXPLAIN accepts
+pub fn is_explainable(sql: &str) -> bool {
+    let sql = sql.trim();
+    ["SELECT", "TABLE", "WITH", "INSERT", "UPDATE", "DELETE", "REPLACE"]
+        .iter()
+        .any(|keyword| find_top_level_keyword(sql, keyword, 0) == Some(0))
+}
+
+// Fill the {{db}} and {{table}} placeholders of a saved snippet from the current
+// context; a placeholder without a value is left for the user to replace
+pub fn fill_placeholders(sql: &str, database: Option<&str>, table: Option<&str>) -> String {
+    let mut filled = sql.to_string();
+    if let Some(database) = database {
+        filled = filled.replace("{{db}}", database);
+    }
+    if let Some(table) = table {
+        filled = filled.replace("{{table}}", table);
+    }
+    filled
+}
+
+// One entry per ? placeholder outside literals and comments, in order: the
+// column it is compared with (`id` for `id = ?`, `name` for `name LIKE ?`) as a
+// hint for the prompt, or an empty string when there is none
+pub fn placeholder_hints(sql: &str) -> Vec<String> {
+    const OPERATOR_WORDS: [&str; 6] = ["LIKE", "NOT", "IN", "IS", "REGEXP", "BETWEEN"];
+    let bytes = sql.as_bytes();
+    let mut hints = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(end) = skip_literal_or_comment(bytes, i) {
+            i = end + 1;
+            continue;
+        }
+        if bytes[i] == b'?' {
+            let mut before = &sql[..i];
+            let hint = loop {
+                before = before.trim_end_matches(|c: char| c.is_whitespace() || "=<>!(".contains(c));
+                // A quoted identifier may hold any character
+                let start = if let Some(quoted) = before.strip_suffix('`') {
+                    quoted.rfind('`').unwrap_or(0)
+                } else {
+                    before
+                        .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == '`'))
+                        .map_or(0, |pos| pos + 1)
+                };
+                let word = &before[start..];
+                if word.eq_ignore_ascii_case("VALUES") {
+                    break String::new();
+                }
+                if word.is_empty() || !OPERATOR_WORDS.contains(&word.to_uppercase().as_str()) {
+                    break word.trim_matches('`').to_string();
+                }
+                before = &before[..start];
+            };
+            hints.push(hint);
+        }
+        i += 1;
+    }
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn splits_on_top_level_semicolons() {
+        assert_eq!(split_statements("SELECT 1; SELECT 2;"), ["SELECT 1", "SELECT 2"]);
+        assert_eq!(split_statements(" ;; SELECT 1 ; "), ["SELECT 1"]);
+    }
+    
+    #[test]
+    fn keeps_trailing_statement_without_semicolon() {
+        assert_eq!(split_statements("SELECT 1;\nSELECT 2  \n"), ["SELECT 1", "SELECT 2"]);
+    }
+    
+    #[test]
+    fn ignores_semicolons_in_quotes_and_backticks() {
+        assert_eq!(
+            split_statements("SELECT 'a;b', \"c;d\" FROM `t;1`; SELECT 2"),
+            ["SELECT 'a;b', \"c;d\" FROM `t;1`", "SELECT 2"]
+        );
+    }
+    
+    #[test]
+    fn honors_escaped_and_doubled_quotes() {
+        assert_eq!(split_statements(r"SELECT 'it\'s;'; SELECT 2"), [r"SELECT 'it\'s;'", "SELECT 2"]);
+        assert_eq!(split_statements("SELECT 'it''s;'; SELECT 2"), ["SELECT 'it''s;'", "SELECT 2"]);
+        assert_eq!(split_statements("SELECT \"a\"\";\"; SELECT 2"), ["SELECT \"a\"\";\"", "SELECT 2"]);
+        assert_eq!(split_statements("SELECT `a``;b`; SELECT 2"), ["SELECT `a``;b`", "SELECT 2"]);
+        // Backslashes don't escape inside backticks
+        assert_eq!(split_statements(r"SELECT `a\`; SELECT 2"), [r"SELECT `a\`", "SELECT 2"]);
+    }
+    
+    #[test]
+    fn ignores_semicolons_in_comments() {
+        assert_eq!(
+            split_statements("SELECT 1 -- one; two\n; SELECT 2"),
+            ["SELECT 1 -- one; two", "SELECT 2"]
+        );
+        assert_eq!(split_statements("SELECT 1 # one; two\n; SELECT 2"), ["SELECT 1 # one; two", "SELECT 2"]);
+        assert_eq!(split_statements("SELECT /* a; b */ 1; SELECT 2"), ["SELECT /* a; b */ 1", "SELECT 2"]);
+    }
+    
+    #[test]
+    fn skips_comments_between_statements() {
+        assert_eq!(split_statements("-- setup;\nSELECT 1;\n/* ; */\n# ;\n"), ["SELECT 1"]);
+        assert!(split_statements("/* only; a comment */").is_empty());
+    }
+    
+    #[test]
+    fn read_only_rejects_executable_comments() {
+        assert!(!is_read_only_statement("SELECT 1 /*! INTO OUTFILE '/tmp/x' */"));
+        assert!(!is_read_only_statement("SELECT 1 /*!;DROP TABLE t*/"));
+        assert!(!is_read_only_statement("SELECT 1 /*M!100000 INTO @x */"));
+        assert!(is_read_only_statement("SELECT '/*!', 1 /* plain */"));
+        assert!(is_read_only_statement("SELECT /*+ MAX_EXECUTION_TIME(1000) */ 1"));
+    }
+    
+    #[test]
+    fn skip_literal_or_comment_stops_on_the_closing_delimiter() {
+        let sql = b"'a\\'b''c' x";
+        assert_eq!(skip_literal_or_comment(sql, 0), Some(8));
+        assert_eq!(skip_literal_or_comment(b"-- c\nx", 0), Some(4));
+        assert_eq!(skip_literal_or_comment(b"# c\nx", 0), Some(3));
+        assert_eq!(skip_literal_or_comment(b"/* c */x", 0), Some(6));
+        assert_eq!(skip_literal_or_comment(b"- x", 0), None);
+        assert_eq!(skip_literal_or_comment(b"x", 0), None);
+    }
+}
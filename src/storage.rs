@@ -0,0 +1,46 @@
+// Crash-safe writes and recovery for the JSON files rmsql keeps on disk
+
+use anyhow::{Context, Result};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// Write next to the target and rename over it, so being killed mid-write leaves
+// either the old file or the new one, never a truncated mix
+pub fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let tmp_path = sibling_path(path, ".tmp");
+    let mut file = File::create(&tmp_path)
+        .with_context(|| format!("Failed to create {}", tmp_path.display()))?;
+    file.write_all(content.as_bytes())
+        .and_then(|()| file.sync_all())
+        .with_context(|| format!("Failed to write {}", tmp_path.display()))?;
+    fs::rename(&tmp_path, path)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+// Move a file that no longer parses to <name>.bak so the app can start from defaults;
+// returns the warning to show
+pub fn set_aside_corrupt(path: &Path, error: &serde_json::Error) -> String {
+    let backup = sibling_path(path, ".bak");
+    match fs::rename(path, &backup) {
+        Ok(()) => format!(
+            "{} was unreadable ({}) - moved it to {} and started fresh",
+            path.display(),
+            error,
+            backup.display()
+        ),
+        Err(e) => format!(
+            "{} was unreadable ({}) and could not be backed up ({}) - starting fresh",
+            path.display(),
+            error,
+            e
+        ),
+    }
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
@@ -0,0 +1,178 @@
+// Color palette for the UI. The `theme` preference picks a built-in scheme or the
+// name of a JSON file in the config dir (themes/<name>.json) that overrides one
+
+use anyhow::{anyhow, Context, Result};
+use ratatui::style::Color;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const BUILTIN_THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    // Titles, header bar and column lists
+    pub header: Color,
+    pub text: Color,
+    // Hints, placeholders and secondary details
+    pub muted: Color,
+    // Labels, table headers and database names
+    pub accent: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    // Selected row or list entry
+    pub highlight_fg: Color,
+    pub highlight_bg: Color,
+    // Search matches, the focused column and the selected completion
+    pub match_fg: Color,
+    pub match_bg: Color,
+    pub status_fg: Color,
+    pub status_bg: Color,
+    pub null: Color,
+    // SQL highlighting in the DDL popup
+    pub keyword: Color,
+    pub identifier: Color,
+    pub string: Color,
+    pub number: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Theme {
+            header: Color::Cyan,
+            text: Color::White,
+            muted: Color::Gray,
+            accent: Color::Yellow,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            highlight_fg: Color::White,
+            highlight_bg: Color::Blue,
+            match_fg: Color::Black,
+            match_bg: Color::Yellow,
+            status_fg: Color::White,
+            status_bg: Color::DarkGray,
+            null: Color::DarkGray,
+            keyword: Color::Blue,
+            identifier: Color::Cyan,
+            string: Color::Green,
+            number: Color::Magenta,
+        }
+    }
+
+    pub fn light() -> Self {
+        Theme {
+            header: Color::Blue,
+            text: Color::Black,
+            muted: Color::DarkGray,
+            accent: Color::Magenta,
+            success: Color::Green,
+            warning: Color::Rgb(160, 100, 0),
+            error: Color::Red,
+            highlight_fg: Color::Black,
+            highlight_bg: Color::LightBlue,
+            match_fg: Color::Black,
+            match_bg: Color::LightYellow,
+            status_fg: Color::Black,
+            status_bg: Color::Gray,
+            null: Color::Gray,
+            keyword: Color::Blue,
+            identifier: Color::Magenta,
+            string: Color::Green,
+            number: Color::Red,
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Theme {
+            header: Color::White,
+            text: Color::White,
+            muted: Color::White,
+            accent: Color::LightYellow,
+            success: Color::LightGreen,
+            warning: Color::LightYellow,
+            error: Color::LightRed,
+            highlight_fg: Color::Black,
+            highlight_bg: Color::White,
+            match_fg: Color::Black,
+            match_bg: Color::LightYellow,
+            status_fg: Color::White,
+            status_bg: Color::Black,
+            null: Color::Gray,
+            keyword: Color::LightCyan,
+            identifier: Color::White,
+            string: Color::LightGreen,
+            number: Color::LightMagenta,
+        }
+    }
+
+    fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    // A built-in theme, or themes/<name>.json from the config dir
+    pub fn load(name: &str) -> Result<Self> {
+        if let Some(theme) = Self::builtin(name) {
+            return Ok(theme);
+        }
+        let path = Self::themes_dir()?.join(format!("{}.json", name));
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Unknown theme '{}' and no {}", name, path.display()))?;
+        Self::from_json(&content).with_context(|| format!("Invalid theme file {}", path.display()))
+    }
+
+    // {"base": "light", "header": "#268bd2", "error": "red", ...}; colors not
+    // listed come from the base theme (dark by default)
+    fn from_json(content: &str) -> Result<Self> {
+        let mut entries: HashMap<String, String> = serde_json::from_str(content)?;
+        let mut theme = match entries.remove("base") {
+            Some(base) => Self::builtin(&base).ok_or_else(|| anyhow!("unknown base theme '{}'", base))?,
+            None => Self::dark(),
+        };
+        for (key, value) in entries {
+            let color = Color::from_str(&value).map_err(|_| anyhow!("invalid color '{}' for {}", value, key))?;
+            let slot = match key.as_str() {
+                "header" => &mut theme.header,
+                "text" => &mut theme.text,
+                "muted" => &mut theme.muted,
+                "accent" => &mut theme.accent,
+                "success" => &mut theme.success,
+                "warning" => &mut theme.warning,
+                "error" => &mut theme.error,
+                "highlight_fg" => &mut theme.highlight_fg,
+                "highlight_bg" => &mut theme.highlight_bg,
+                "match_fg" => &mut theme.match_fg,
+                "match_bg" => &mut theme.match_bg,
+                "status_fg" => &mut theme.status_fg,
+                "status_bg" => &mut theme.status_bg,
+                "null" => &mut theme.null,
+                "keyword" => &mut theme.keyword,
+                "identifier" => &mut theme.identifier,
+                "string" => &mut theme.string,
+                "number" => &mut theme.number,
+                _ => return Err(anyhow!("unknown theme color '{}'", key)),
+            };
+            *slot = color;
+        }
+        Ok(theme)
+    }
+
+    fn themes_dir() -> Result<PathBuf> {
+        let config_dir = dirs::config_dir()
+            .context("Failed to get config directory")?;
+        Ok(config_dir.join("rmsql").join("themes"))
+    }
+}
@@ -0,0 +1,98 @@
+// SSH port-forwarding through a bastion host, using the system's OpenSSH client
+
+use anyhow::{anyhow, Context, Result};
+use std::io::Read;
+use std::net::{TcpListener, TcpStream};
+use std::process::{Child, Command, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::connection_config::ConnectionConfig;
+
+const TUNNEL_TIMEOUT: Duration = Duration::from_secs(15);
+
+// A running `ssh -L` forward to the MySQL server; the ssh process is stopped on drop
+pub struct SshTunnel {
+    child: Child,
+    // Reads ssh's stderr as it comes so a chatty ssh can't block on a full pipe
+    stderr: Option<JoinHandle<String>>,
+    pub local_port: u16,
+}
+
+impl SshTunnel {
+    pub fn open(config: &ConnectionConfig, ssh_host: &str) -> Result<Self> {
+        // A leading dash would make ssh read the host or user as an option
+        let ssh_user = config.ssh_user.as_deref().filter(|user| !user.is_empty());
+        if ssh_host.starts_with('-') || ssh_user.is_some_and(|user| user.starts_with('-')) {
+            return Err(anyhow!("Invalid SSH host or user: it must not start with '-'"));
+        }
+
+        // Let the OS pick a free local port for the forward
+        let local_port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .context("Failed to reserve a local port for the SSH tunnel")?
+            .port();
+
+        let destination = match ssh_user {
+            Some(user) => format!("{}@{}", user, ssh_host),
+            None => ssh_host.to_string(),
+        };
+
+        let mut command = Command::new("ssh");
+        command
+            .arg("-N")
+            .args(["-o", "ExitOnForwardFailure=yes", "-o", "BatchMode=yes"])
+            .arg("-L")
+            .arg(format!("127.0.0.1:{}:{}:{}", local_port, config.host, config.port))
+            .arg("-p")
+            .arg(config.ssh_port.unwrap_or(22).to_string());
+        if let Some(key_path) = config.ssh_key_path.as_deref().filter(|path| !path.is_empty()) {
+            command.arg("-i").arg(crate::expand_home(key_path));
+        }
+        command
+            .arg("--")
+            .arg(destination)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+
+        let mut child = command
+            .spawn()
+            .context("Failed to start ssh for the tunnel - is the OpenSSH client installed?")?;
+        let stderr = child.stderr.take().map(|mut pipe| {
+            std::thread::spawn(move || {
+                let mut text = String::new();
+                let _ = pipe.read_to_string(&mut text);
+                text
+            })
+        });
+        let mut tunnel = SshTunnel { child, stderr, local_port };
+        tunnel.wait_until_ready(ssh_host)?;
+        Ok(tunnel)
+    }
+
+    // Wait for the forward to accept connections, reporting ssh's own error if it exits
+    fn wait_until_ready(&mut self, ssh_host: &str) -> Result<()> {
+        let started = Instant::now();
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                let stderr = self.stderr.take().and_then(|reader| reader.join().ok()).unwrap_or_default();
+                return Err(anyhow!("SSH tunnel via {} failed ({}): {}", ssh_host, status, stderr.trim()));
+            }
+            if TcpStream::connect(("127.0.0.1", self.local_port)).is_ok() {
+                return Ok(());
+            }
+            if started.elapsed() > TUNNEL_TIMEOUT {
+                return Err(anyhow!("Timed out setting up the SSH tunnel via {}", ssh_host));
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
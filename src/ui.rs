@@ -1,12 +1,275 @@
+use chrono::{Local, NaiveDateTime};
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Flex, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Row, Table},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Table, TableState, Tabs},
     Frame,
 };
 
-use crate::navigation::{NavigationState, ViewMode};
+use std::borrow::Cow;
+
+use crate::database::{self, quote_identifier, ColumnKey, ExplainComparison};
+use crate::navigation::{self, NavigationState, SchemaPopup, SnippetPicker, SqlResult, ViewMode};
+use crate::preferences::{PreferencesEditor, PREFERENCE_FIELDS};
+use crate::theme::Theme;
+
+// Bytes shown in the hex dump of a binary cell
+const HEX_DUMP_LIMIT: usize = 4096;
+
+// Characters of a cell shown before "...": the default, the bounds and the step of ( and )
+pub const DEFAULT_CELL_DISPLAY_CHARS: usize = 50;
+pub const MIN_CELL_DISPLAY_CHARS: usize = 10;
+pub const MAX_CELL_DISPLAY_CHARS: usize = 500;
+const CELL_DISPLAY_STEP: usize = 10;
+
+// DATETIME/TIMESTAMP values are taken as UTC; in local-time mode they are converted
+// for display, keeping fractional seconds. Anything else is shown unchanged.
+fn display_time(value: &str, local_time: bool) -> Cow<'_, str> {
+    if !local_time {
+        return Cow::Borrowed(value);
+    }
+    match NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f") {
+        Ok(utc) => {
+            let format = if value.contains('.') { "%Y-%m-%d %H:%M:%S%.f" } else { "%Y-%m-%d %H:%M:%S" };
+            Cow::Owned(utc.and_utc().with_timezone(&Local).format(format).to_string())
+        },
+        Err(_) => Cow::Borrowed(value),
+    }
+}
+
+// A list entry with the characters matched by the list filter highlighted
+fn highlighted_line(prefix: &str, name: &str, filter: &str, color: Color) -> Line<'static> {
+    let base = Style::default().fg(color);
+    let matched = base.add_modifier(Modifier::UNDERLINED | Modifier::BOLD);
+    let positions = navigation::match_positions(name, filter);
+    
+    let mut spans = vec![Span::styled(prefix.to_string(), base)];
+    spans.extend(name.chars().enumerate().map(|(i, c)| {
+        Span::styled(c.to_string(), if positions.contains(&i) { matched } else { base })
+    }));
+    Line::from(spans)
+}
+
+// Offset, hex and printable ASCII, 16 bytes a line, like hexdump -C; long values
+// are cut off after HEX_DUMP_LIMIT bytes
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut lines: Vec<String> = bytes
+        .chunks(16)
+        .take(HEX_DUMP_LIMIT / 16)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+                .collect();
+            format!("{:08x}  {:<47}  |{}|", i * 16, hex.join(" "), ascii)
+        })
+        .collect();
+    if bytes.len() > HEX_DUMP_LIMIT {
+        lines.push(format!("... {} more bytes", bytes.len() - HEX_DUMP_LIMIT));
+    }
+    lines.join("\n")
+}
+
+// Basic SQL coloring for SHOW CREATE output: uppercase keywords, `identifiers`,
+// 'strings' and numbers each get their own color
+fn highlight_sql(sql: &str, theme: &Theme) -> Text<'static> {
+    let keyword = Style::default().fg(theme.keyword).add_modifier(Modifier::BOLD);
+    let identifier = Style::default().fg(theme.identifier);
+    let string = Style::default().fg(theme.string);
+    let number = Style::default().fg(theme.number);
+    
+    let lines: Vec<Line> = sql
+        .lines()
+        .map(|line| {
+            let chars: Vec<char> = line.chars().collect();
+            let mut spans = Vec::new();
+            let mut i = 0;
+            while i < chars.len() {
+                let c = chars[i];
+                let start = i;
+                let style = if c == '`' || c == '\'' {
+                    i += 1;
+                    while i < chars.len() && chars[i] != c {
+                        i += 1;
+                    }
+                    i = (i + 1).min(chars.len());
+                    if c == '`' { identifier } else { string }
+                } else if c.is_alphanumeric() || c == '_' {
+                    while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                        i += 1;
+                    }
+                    let word: String = chars[start..i].iter().collect();
+                    if word.chars().all(|c| c.is_ascii_digit()) {
+                        number
+                    } else if word.len() > 1 && word.chars().all(|c| c.is_ascii_uppercase() || c == '_') {
+                        keyword
+                    } else {
+                        Style::default()
+                    }
+                } else {
+                    i += 1;
+                    Style::default()
+                };
+                spans.push(Span::styled(chars[start..i].iter().collect::<String>(), style));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    Text::from(lines)
+}
+
+// Indented form of a JSON value, keeping the key order. Values of JSON columns
+// (typed) may be scalars; other text only counts when it is an object or array.
+// None when the value doesn't parse
+fn pretty_json(value: &str, typed: bool) -> Option<String> {
+    let trimmed = value.trim_start();
+    if !(typed || trimmed.starts_with('{') || trimmed.starts_with('[')) {
+        return None;
+    }
+    let parsed: serde_json::Value = serde_json::from_str(value).ok()?;
+    serde_json::to_string_pretty(&parsed).ok()
+}
+
+// Coloring for pretty-printed JSON: keys, strings, numbers and the
+// true/false/null literals each get their own color
+fn highlight_json(json: &str, theme: &Theme) -> Vec<Line<'static>> {
+    let key = Style::default().fg(theme.identifier);
+    let string = Style::default().fg(theme.string);
+    let number = Style::default().fg(theme.number);
+    let literal = Style::default().fg(theme.keyword).add_modifier(Modifier::BOLD);
+    
+    json.lines()
+        .map(|line| {
+            let chars: Vec<char> = line.chars().collect();
+            let mut spans = Vec::new();
+            let mut i = 0;
+            while i < chars.len() {
+                let c = chars[i];
+                let start = i;
+                let style = if c == '"' {
+                    i += 1;
+                    while i < chars.len() && chars[i] != '"' {
+                        // Skip the escaped character so \" doesn't end the string
+                        i += if chars[i] == '\\' { 2 } else { 1 };
+                    }
+                    i = (i + 1).min(chars.len());
+                    let rest = chars[i..].iter().find(|c| !c.is_whitespace());
+                    if rest == Some(&':') { key } else { string }
+                } else if c == '-' || c.is_ascii_digit() {
+                    while i < chars.len() && (chars[i].is_ascii_alphanumeric() || matches!(chars[i], '-' | '+' | '.')) {
+                        i += 1;
+                    }
+                    number
+                } else if c.is_ascii_alphabetic() {
+                    while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                        i += 1;
+                    }
+                    literal
+                } else {
+                    i += 1;
+                    Style::default()
+                };
+                spans.push(Span::styled(chars[start..i].iter().collect::<String>(), style));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn list_title(title: String, navigation: &NavigationState) -> String {
+    if navigation.list_filter.is_empty() {
+        title
+    } else {
+        format!("{} [filter: {}]", title, navigation.list_filter)
+    }
+}
+
+// SQL NULL is dimmed so it can't be mistaken for the string 'NULL'
+fn null_style(theme: &Theme) -> Style {
+    Style::default().fg(theme.null).add_modifier(Modifier::ITALIC)
+}
+
+fn null_cell(theme: &Theme) -> Cell<'static> {
+    Cell::from("NULL").style(null_style(theme))
+}
+
+// Spaces, tabs and line breaks as visible characters
+fn reveal_whitespace(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            ' ' => '·',
+            '\t' => '→',
+            '\n' => '↵',
+            c if c.is_whitespace() => '␣',
+            c => c,
+        })
+        .collect()
+}
+
+// A marker for an empty or all-whitespace string, so neither reads as the other or as NULL;
+// None for other values, or when the markers are turned off
+fn blank_cell(value: &str, markers: bool, theme: &Theme) -> Option<Cell<'static>> {
+    if !markers || !value.trim().is_empty() {
+        return None;
+    }
+    let marker = if value.is_empty() { "(empty)".to_string() } else { reveal_whitespace(value) };
+    Some(Cell::from(marker).style(Style::default().fg(theme.muted)))
+}
+
+// A line of the cell detail popup with its trailing whitespace made visible
+fn reveal_trailing_whitespace(line: &str, theme: &Theme) -> Line<'static> {
+    let content = line.trim_end();
+    let trailing = &line[content.len()..];
+    if trailing.is_empty() {
+        return Line::from(line.to_string());
+    }
+    Line::from(vec![
+        Span::raw(content.to_string()),
+        Span::styled(reveal_whitespace(trailing), Style::default().fg(theme.muted)),
+    ])
+}
+
+// Black on the light badge colors, white on the dark ones
+fn badge_text_color(background: Color) -> Color {
+    match background {
+        Color::Yellow | Color::Green | Color::Cyan | Color::White | Color::Gray
+        | Color::LightYellow | Color::LightGreen | Color::LightCyan | Color::LightBlue
+        | Color::LightMagenta | Color::LightRed => Color::Black,
+        Color::Rgb(r, g, b) if (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000 > 128 => Color::Black,
+        _ => Color::White,
+    }
+}
+
+// Numbers are right-aligned so their magnitudes line up
+fn aligned_cell(text: String, numeric: bool) -> Cell<'static> {
+    if numeric {
+        Cell::from(Text::from(text).right_aligned())
+    } else {
+        Cell::from(text)
+    }
+}
+
+fn is_numeric(numeric_columns: &[bool], index: usize) -> bool {
+    numeric_columns.get(index).copied().unwrap_or(false)
+}
 
 // Helper function to truncate UTF-8 strings safely
 fn truncate_utf8(s: &str, max_bytes: usize) -> &str {
@@ -25,11 +288,131 @@ fn truncate_utf8(s: &str, max_bytes: usize) -> &str {
     ""
 }
 
-pub struct AppUI;
+// Cut a cell to max_chars characters, the last three of them being "..."
+fn truncate_cell(cell: Cow<str>, max_chars: usize) -> String {
+    if cell.chars().nth(max_chars).is_none() {
+        return cell.into_owned();
+    }
+    let keep = cell.char_indices().nth(max_chars.saturating_sub(3)).map_or(cell.len(), |(i, _)| i);
+    format!("{}...", truncate_utf8(&cell, keep))
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
+
+// Header, main content and status bar, shared by draw and mouse_target
+fn main_layout(area: Rect) -> [Rect; 3] {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Main content
+            Constraint::Length(3), // Status bar
+        ])
+        .areas(area)
+}
+
+// Column info box above the data table, and the table itself
+fn table_data_layout(area: Rect) -> [Rect; 2] {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(5), // Column info
+            Constraint::Min(0),    // Table data
+        ])
+        .areas(area)
+}
+
+// Widths of the visible data columns inside the table borders, after the relative
+// number column when it is on
+fn data_column_constraints(navigation: &NavigationState, num_visible_cols: usize, available_width: u16) -> Vec<Constraint> {
+    let number_width = row_number_width(navigation);
+    let mut constraints = Vec::new();
+    let mut available_width = available_width;
+    if number_width > 0 {
+        constraints.push(Constraint::Length(number_width));
+        available_width = available_width.saturating_sub(number_width + 1);
+    }
+    
+    if navigation.expanded_columns {
+        // In expanded mode, manual widths first, the rest shared (minimum 20 chars each)
+        constraints.extend(navigation.expanded_column_widths(available_width).into_iter().map(Constraint::Length));
+    } else {
+        // In normal mode, distribute space evenly
+        let col_width = available_width / num_visible_cols.max(1) as u16;
+        constraints.extend(vec![Constraint::Length(col_width); num_visible_cols.max(1)]);
+    }
+    constraints
+}
+
+fn column_key(navigation: &NavigationState, column: usize) -> ColumnKey {
+    navigation.table_column_keys.get(column).copied().unwrap_or(ColumnKey::None)
+}
+
+// Vim's relativenumber: how far a row is from the selected one, 0 on the selected row
+fn relative_number(position: usize, selected: Option<usize>, width: usize) -> String {
+    format!("{:>width$}", position.abs_diff(selected.unwrap_or(0)), width = width)
+}
+
+// Width of the relative number column of the data table; 0 when it is off
+fn row_number_width(navigation: &NavigationState) -> u16 {
+    if navigation.relative_numbers {
+        navigation.table_rows.len().to_string().len() as u16
+    } else {
+        0
+    }
+}
+
+// First entry drawn when a list or table of `height` rows is rendered from a fresh
+// offset, as ui does with cloned states: the selection ends up on the last line
+fn first_visible_row(selected: Option<usize>, height: u16) -> usize {
+    (selected.unwrap_or(0) + 1).saturating_sub(height as usize)
+}
+
+// What a mouse click in the main view lands on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseTarget {
+    // Position in the shown (possibly filtered) list, or index into the data rows
+    Row(usize),
+    // Index into table_columns
+    ColumnHeader(usize),
+}
+
+pub struct AppUI {
+    pub theme: Theme,
+    pub cell_display_chars: usize,
+}
 
 impl AppUI {
-    pub fn new() -> Self {
-        AppUI
+    pub fn new(theme: Theme, cell_display_chars: usize) -> Self {
+        AppUI { theme, cell_display_chars }
+    }
+    
+    // Show more or fewer characters per cell; returns the new length
+    pub fn resize_cells(&mut self, grow: bool) -> usize {
+        self.cell_display_chars = if grow {
+            (self.cell_display_chars + CELL_DISPLAY_STEP).min(MAX_CELL_DISPLAY_CHARS)
+        } else {
+            self.cell_display_chars.saturating_sub(CELL_DISPLAY_STEP).max(MIN_CELL_DISPLAY_CHARS)
+        };
+        self.cell_display_chars
     }
     
     pub fn draw(
@@ -38,14 +421,7 @@ impl AppUI {
         navigation: &NavigationState,
         status_message: &str,
     ) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Min(0),    // Main content
-                Constraint::Length(3), // Status bar
-            ])
-            .split(f.area());
+        let chunks = main_layout(f.area());
         
         // Draw header
         self.draw_header(f, chunks[0], navigation);
@@ -56,10 +432,517 @@ impl AppUI {
             ViewMode::Tables => self.draw_tables(f, chunks[1], navigation),
             ViewMode::TableData => self.draw_table_data(f, chunks[1], navigation),
             ViewMode::SqlEditor => self.draw_sql_editor(f, chunks[1], navigation),
+            ViewMode::Replication => self.draw_replication(f, chunks[1], navigation),
+            ViewMode::Binlog => self.draw_binlog(f, chunks[1], navigation),
+            ViewMode::ProcessList => self.draw_process_list(f, chunks[1], navigation),
         }
         
         // Draw status bar
         self.draw_status_bar(f, chunks[2], status_message, navigation);
+        
+        // Draw the affected-rows preview on top of everything else
+        if let Some(preview) = &navigation.affected_rows_preview {
+            let area = centered_rect(80, 60, f.area());
+            f.render_widget(Clear, area);
+            self.draw_sql_result(f, area, preview, navigation.local_time, navigation.blank_markers, 0);
+        }
+        
+        if let Some(selected) = navigation.recent_queries_selected {
+            self.draw_recent_queries(f, chunks[1], navigation, selected);
+        }
+        
+        if let Some(picker) = &navigation.snippet_picker {
+            self.draw_snippet_picker(f, chunks[1], picker);
+        }
+        
+        if let Some(selected) = navigation.column_picker {
+            self.draw_column_picker(f, navigation, selected);
+        }
+        
+        if let Some((column, value)) = &navigation.cell_detail {
+            self.draw_cell_detail(f, navigation, column, value.as_deref());
+        }
+        
+        if let Some(editor) = &navigation.preferences_editor {
+            self.draw_preferences(f, editor);
+        }
+        
+        if let Some((table, ddl)) = &navigation.ddl_popup {
+            self.draw_ddl_popup(f, table, ddl, navigation.ddl_popup_scroll);
+        }
+        
+        if let Some(popup) = &navigation.schema_popup {
+            self.draw_schema_popup(f, popup);
+        }
+        
+        if let Some(rows) = &navigation.server_info {
+            self.draw_server_info(f, rows);
+        }
+    }
+    
+    // Map a click at (x, y) to a list entry, data row or column header of the browse views
+    pub fn mouse_target(&self, screen: Rect, navigation: &NavigationState, x: u16, y: u16) -> Option<MouseTarget> {
+        let [_, content, _] = main_layout(screen);
+        let (list_len, selected) = match navigation.mode {
+            ViewMode::Databases => (
+                navigation.visible_list_items(ViewMode::Databases, &navigation.databases).len(),
+                navigation.database_list_state.selected(),
+            ),
+            ViewMode::Tables => (
+                navigation.visible_list_items(ViewMode::Tables, &navigation.tables).len(),
+                navigation.table_list_state.selected(),
+            ),
+            ViewMode::TableData => return self.table_data_target(content, navigation, x, y),
+            _ => return None,
+        };
+        
+        let inner = content.inner(Margin::new(1, 1));
+        if !inner.contains((x, y).into()) {
+            return None;
+        }
+        let position = first_visible_row(selected, inner.height) + (y - inner.y) as usize;
+        (position < list_len).then_some(MouseTarget::Row(position))
+    }
+    
+    fn table_data_target(&self, content: Rect, navigation: &NavigationState, x: u16, y: u16) -> Option<MouseTarget> {
+        if navigation.table_columns.is_empty() || navigation.table_rows.is_empty() {
+            return None;
+        }
+        let [_, table_area] = table_data_layout(content);
+        let inner = table_area.inner(Margin::new(1, 1));
+        if !inner.contains((x, y).into()) {
+            return None;
+        }
+        
+        // The header line, then a blank margin line, then the rows
+        if y == inner.y {
+            let visible = navigation.visible_column_indices();
+            let constraints = data_column_constraints(navigation, visible.len(), inner.width);
+            let columns = Layout::horizontal(constraints).flex(Flex::Start).spacing(1).split(inner);
+            return columns
+                .iter()
+                .position(|column| x >= column.x && x < column.x + column.width)
+                .and_then(|i| i.checked_sub(usize::from(navigation.relative_numbers)))
+                .and_then(|i| visible.get(i).copied())
+                .map(MouseTarget::ColumnHeader);
+        }
+        let rows_top = inner.y + 2;
+        if y < rows_top {
+            return None;
+        }
+        let rows_height = inner.height.saturating_sub(2);
+        let row = first_visible_row(navigation.data_table_state.selected(), rows_height) + (y - rows_top) as usize;
+        (row < navigation.table_rows.len()).then_some(MouseTarget::Row(row))
+    }
+    
+    fn draw_server_info(&self, f: &mut Frame, rows: &[(String, String)]) {
+        let area = centered_rect(60, 60, f.area());
+        f.render_widget(Clear, area);
+        let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+        let lines: Vec<Line> = rows
+            .iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(format!("{:<width$}  ", format!("{}:", label), width = width + 1), Style::default().fg(self.theme.accent)),
+                    Span::raw(value.clone()),
+                ])
+            })
+            .collect();
+        let panel = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Server info - Esc to close"))
+            .wrap(ratatui::widgets::Wrap { trim: true });
+        f.render_widget(panel, area);
+    }
+    
+    fn draw_cell_detail(&self, f: &mut Frame, navigation: &NavigationState, column: &str, value: Option<&str>) {
+        let area = centered_rect(80, 70, f.area());
+        f.render_widget(Clear, area);
+        let (lines, length) = match (value, &navigation.cell_detail_bytes) {
+            (_, Some(bytes)) => (
+                Some(hex_dump(bytes).lines().map(|line| Line::from(line.to_string())).collect::<Vec<_>>()),
+                format!("{} bytes of binary data, w writes them to a file", bytes.len()),
+            ),
+            (Some(value), None) => {
+                let json = if navigation.cell_detail_json { "JSON column, " } else { "" };
+                match pretty_json(value, navigation.cell_detail_json).filter(|_| !navigation.cell_detail_raw) {
+                    Some(pretty) => (
+                        Some(highlight_json(&pretty, &self.theme)),
+                        format!("{} chars, {}pretty-printed, p for raw", value.chars().count(), json),
+                    ),
+                    None => {
+                        let shown = display_time(value, navigation.local_time);
+                        let lines = if !navigation.blank_markers {
+                            shown.lines().map(|line| Line::from(line.to_string())).collect()
+                        } else if shown.is_empty() {
+                            vec![Line::styled("(empty string)", Style::default().fg(self.theme.muted))]
+                        } else {
+                            shown.split('\n').map(|line| reveal_trailing_whitespace(line, &self.theme)).collect()
+                        };
+                        (Some(lines), format!("{}{} chars", json, value.chars().count()))
+                    },
+                }
+            },
+            (None, None) => (None, "SQL NULL".to_string()),
+        };
+        
+        let text = match lines {
+            Some(lines) if navigation.cell_detail_line_numbers => {
+                let width = lines.len().max(1).to_string().len();
+                let number_style = Style::default().fg(self.theme.muted);
+                Text::from(
+                    lines
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, mut line)| {
+                            line.spans.insert(0, Span::styled(format!("{:>width$} │ ", i + 1, width = width), number_style));
+                            line
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            },
+            Some(lines) => Text::from(lines),
+            None => Text::styled("NULL", null_style(&self.theme)),
+        };
+        
+        let wrap = if navigation.cell_detail_wrap { "on" } else { "off" };
+        let mut detail = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("{} ({}) - w wrap {}, # line numbers, Esc to close", column, length, wrap))
+        );
+        if navigation.cell_detail_wrap {
+            detail = detail
+                .wrap(ratatui::widgets::Wrap { trim: false })
+                .scroll((navigation.cell_detail_scroll, 0));
+        } else {
+            detail = detail.scroll((navigation.cell_detail_scroll, navigation.cell_detail_hscroll));
+        }
+        f.render_widget(detail, area);
+    }
+    
+    fn draw_preferences(&self, f: &mut Frame, editor: &PreferencesEditor) {
+        let area = centered_rect(60, 60, f.area());
+        f.render_widget(Clear, area);
+        
+        let label_width = PREFERENCE_FIELDS.iter().map(|field| field.label().len()).max().unwrap_or(0);
+        let items: Vec<ListItem> = PREFERENCE_FIELDS
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let value = match &editor.editing {
+                    Some(input) if i == editor.selected => format!("{}_", input),
+                    _ => field.value(&editor.prefs),
+                };
+                let value_style = if field.is_toggle() {
+                    Style::default().fg(self.theme.header)
+                } else {
+                    Style::default().fg(self.theme.accent)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{:<width$}  ", field.label(), width = label_width)),
+                    Span::styled(value, value_style),
+                ]))
+            })
+            .collect();
+        
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Preferences (Space/Enter toggle or edit, s save, Esc discard)")
+            )
+            .highlight_style(Style::default().fg(self.theme.highlight_fg).bg(self.theme.highlight_bg).add_modifier(Modifier::BOLD))
+            .highlight_symbol("► ");
+        
+        let mut state = ListState::default();
+        state.select(Some(editor.selected));
+        f.render_stateful_widget(list, area, &mut state);
+    }
+    
+    fn draw_ddl_popup(&self, f: &mut Frame, table: &str, ddl: &str, scroll: u16) {
+        let area = centered_rect(80, 80, f.area());
+        f.render_widget(Clear, area);
+        let popup = Paragraph::new(highlight_sql(ddl, &self.theme))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("CREATE TABLE {} - y copy, w write .sql, Esc close", table))
+            )
+            .wrap(ratatui::widgets::Wrap { trim: false })
+            .scroll((scroll, 0));
+        f.render_widget(popup, area);
+    }
+    
+    fn draw_schema_popup(&self, f: &mut Frame, popup: &SchemaPopup) {
+        let area = centered_rect(80, 80, f.area());
+        f.render_widget(Clear, area);
+        
+        // Indexes and foreign keys get one line each (plus borders and header)
+        // below the columns, which take the remaining space
+        let section_height = |count: usize| (count.max(1) as u16 + 3).min(area.height / 4);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),
+                Constraint::Length(section_height(popup.indexes.len())),
+                Constraint::Length(section_height(popup.foreign_keys.len())),
+            ])
+            .split(area);
+        let header_style = Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD);
+        
+        let rows: Vec<Row> = popup.columns
+            .iter()
+            .skip(popup.scroll)
+            .map(|column| {
+                let key_style = match column.key.as_str() {
+                    "PRI" => Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD),
+                    "UNI" => Style::default().fg(self.theme.header),
+                    _ => Style::default().fg(self.theme.muted),
+                };
+                let default = match &column.default {
+                    Some(value) => Cell::from(value.clone()),
+                    None => Cell::from("NULL").style(Style::default().fg(self.theme.null)),
+                };
+                Row::new(vec![
+                    Cell::from(column.name.clone()).style(Style::default().fg(self.theme.identifier)),
+                    Cell::from(column.column_type.clone()),
+                    Cell::from(if column.nullable { "YES" } else { "NO" }),
+                    Cell::from(column.key.clone()).style(key_style),
+                    default,
+                    Cell::from(column.extra.clone()).style(Style::default().fg(self.theme.muted)),
+                ])
+            })
+            .collect();
+        
+        let name_width = popup.columns.iter().map(|column| column.name.chars().count()).max().unwrap_or(0).max(6);
+        let type_width = popup.columns.iter().map(|column| column.column_type.chars().count()).max().unwrap_or(0).max(4);
+        let widths = [
+            Constraint::Length(name_width as u16),
+            Constraint::Length(type_width as u16),
+            Constraint::Length(4),
+            Constraint::Length(3),
+            Constraint::Min(7),
+            Constraint::Min(5),
+        ];
+        let columns_table = Table::new(rows, widths)
+            .header(Row::new(["Column", "Type", "Null", "Key", "Default", "Extra"]).style(header_style))
+            .column_spacing(2)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Columns of {} - C DDL, Esc close", popup.table))
+            );
+        f.render_widget(columns_table, chunks[0]);
+        
+        let index_rows: Vec<Row> = popup.indexes
+            .iter()
+            .map(|index| {
+                let kind = match (index.name.as_str(), index.unique) {
+                    ("PRIMARY", _) => "PRIMARY",
+                    (_, true) => "UNIQUE",
+                    _ => "",
+                };
+                Row::new(vec![
+                    Cell::from(index.name.clone()).style(Style::default().fg(self.theme.identifier)),
+                    Cell::from(kind).style(Style::default().fg(self.theme.accent)),
+                    Cell::from(index.index_type.clone()).style(Style::default().fg(self.theme.muted)),
+                    Cell::from(index.columns.join(", ")),
+                ])
+            })
+            .collect();
+        let index_name_width = popup.indexes.iter().map(|index| index.name.chars().count()).max().unwrap_or(0).max(5);
+        let indexes_table = Table::new(index_rows, [
+            Constraint::Length(index_name_width as u16),
+            Constraint::Length(7),
+            Constraint::Length(8),
+            Constraint::Min(10),
+        ])
+            .header(Row::new(["Index", "Kind", "Type", "Columns"]).style(header_style))
+            .column_spacing(2)
+            .block(Block::default().borders(Borders::ALL).title(format!("Indexes ({})", popup.indexes.len())));
+        f.render_widget(indexes_table, chunks[1]);
+        
+        let foreign_key_rows: Vec<Row> = popup.foreign_keys
+            .iter()
+            .map(|key| {
+                let target = if key.referenced_database.is_empty() {
+                    key.referenced_table.clone()
+                } else {
+                    format!("{}.{}", key.referenced_database, key.referenced_table)
+                };
+                Row::new(vec![
+                    Cell::from(key.name.clone()).style(Style::default().fg(self.theme.identifier)),
+                    Cell::from(key.columns.join(", ")),
+                    Cell::from(format!("{} ({})", target, key.referenced_columns.join(", "))),
+                ])
+            })
+            .collect();
+        let foreign_key_name_width = popup.foreign_keys.iter().map(|key| key.name.chars().count()).max().unwrap_or(0).max(11);
+        let foreign_keys_table = Table::new(foreign_key_rows, [
+            Constraint::Length(foreign_key_name_width as u16),
+            Constraint::Percentage(30),
+            Constraint::Min(10),
+        ])
+            .header(Row::new(["Foreign key", "Columns", "References"]).style(header_style))
+            .column_spacing(2)
+            .row_highlight_style(Style::default().fg(self.theme.highlight_fg).bg(self.theme.highlight_bg))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Foreign keys ({}) - Tab select, Enter open table", popup.foreign_keys.len()))
+            );
+        let mut state = TableState::default();
+        if !popup.foreign_keys.is_empty() {
+            state.select(Some(popup.selected_foreign_key));
+        }
+        f.render_stateful_widget(foreign_keys_table, chunks[2], &mut state);
+    }
+    
+    // Compact list just above the status bar; queries are flattened to one line
+    fn draw_recent_queries(&self, f: &mut Frame, content: Rect, navigation: &NavigationState, selected: usize) {
+        let queries = navigation.recent_queries();
+        let height = (queries.len() as u16 + 2).min(content.height);
+        let area = Rect {
+            x: content.x,
+            y: content.bottom().saturating_sub(height),
+            width: content.width,
+            height,
+        };
+        let max_len = area.width.saturating_sub(8) as usize;
+        
+        let items: Vec<ListItem> = queries
+            .iter()
+            .enumerate()
+            .map(|(i, sql)| {
+                let flat = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+                let text = if flat.len() > max_len {
+                    format!("{}...", truncate_utf8(&flat, max_len.saturating_sub(3)))
+                } else {
+                    flat
+                };
+                let style = if i == selected {
+                    Style::default().fg(self.theme.match_fg).bg(self.theme.match_bg)
+                } else {
+                    Style::default().fg(self.theme.text)
+                };
+                ListItem::new(format!("{} {}", i + 1, text)).style(style)
+            })
+            .collect();
+        
+        let list = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Recently run (1-3 or Enter to load, Esc to close)")
+        );
+        f.render_widget(Clear, area);
+        f.render_widget(list, area);
+    }
+    
+    fn draw_snippet_picker(&self, f: &mut Frame, content: Rect, picker: &SnippetPicker) {
+        let height = (picker.snippets.len() as u16 + 2).min(content.height / 2).max(3);
+        let area = Rect {
+            x: content.x,
+            y: content.bottom().saturating_sub(height),
+            width: content.width,
+            height,
+        };
+        let name_width = picker.snippets.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0).min(30);
+        let max_len = (area.width as usize).saturating_sub(name_width + 6);
+        
+        let items: Vec<ListItem> = picker
+            .snippets
+            .iter()
+            .map(|(name, sql)| {
+                let flat = sql.split_whitespace().collect::<Vec<_>>().join(" ");
+                let text = if flat.len() > max_len {
+                    format!("{}...", truncate_utf8(&flat, max_len.saturating_sub(3)))
+                } else {
+                    flat
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<width$}  ", name, width = name_width), Style::default().fg(self.theme.accent)),
+                    Span::styled(text, Style::default().fg(self.theme.text)),
+                ]))
+            })
+            .collect();
+        
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Snippets (Enter to load, d to delete, Esc to close)")
+            )
+            .highlight_style(Style::default().fg(self.theme.match_fg).bg(self.theme.match_bg));
+        let mut state = ListState::default();
+        state.select(Some(picker.selected));
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut state);
+    }
+    
+    // Every column of the open table with a checkbox; unchecked ones are left out of the grid
+    fn draw_column_picker(&self, f: &mut Frame, navigation: &NavigationState, selected: usize) {
+        let names = navigation.table_column_names();
+        let items: Vec<ListItem> = navigation
+            .table_columns
+            .iter()
+            .zip(&names)
+            .enumerate()
+            .map(|(i, (label, name))| {
+                let hidden = navigation.hidden_columns.contains(name);
+                let style = if hidden {
+                    Style::default().fg(self.theme.muted)
+                } else {
+                    Style::default().fg(self.theme.text)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(if hidden { "[ ] " } else { "[x] " }, Style::default().fg(self.theme.accent)),
+                    Span::styled(format!("{}{}", column_key(navigation, i).marker(), label), style),
+                ]))
+            })
+            .collect();
+        
+        let shown = names.len() - navigation.hidden_columns.iter().filter(|name| names.contains(name)).count();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(
+                        "Columns {}/{} shown (Space toggle, a show all, Esc close)",
+                        shown,
+                        names.len()
+                    ))
+            )
+            .highlight_style(Style::default().fg(self.theme.highlight_fg).bg(self.theme.highlight_bg));
+        let mut state = ListState::default();
+        state.select(Some(selected));
+        let area = centered_rect(50, 70, f.area());
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut state);
+    }
+    
+    pub fn draw_lock_screen(&self, f: &mut Frame, input_len: usize, error: Option<&str>) {
+        let area = f.area();
+        f.render_widget(Clear, area);
+        
+        let mut lines = vec![
+            Line::from(Span::styled(
+                "RMSQL is locked after inactivity",
+                Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from("Enter the connection password and press Enter to unlock"),
+            Line::from(""),
+            Line::from(format!("Password: {}_", "*".repeat(input_len))),
+        ];
+        if let Some(error) = error {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(error.to_string(), Style::default().fg(self.theme.error))));
+        }
+        
+        let lock = Paragraph::new(lines)
+            .alignment(ratatui::layout::Alignment::Center)
+            .block(Block::default().borders(Borders::ALL).title("Locked"));
+        f.render_widget(lock, centered_rect(60, 40, area));
     }
     
     fn draw_header(&self, f: &mut Frame, area: Rect, navigation: &NavigationState) {
@@ -68,31 +951,56 @@ impl AppUI {
             ViewMode::Tables => "RMSQL - Tables",
             ViewMode::TableData => "RMSQL - Table Data",
             ViewMode::SqlEditor => "RMSQL - SQL Editor",
+            ViewMode::Replication => "RMSQL - Replication Status",
+            ViewMode::Binlog => "RMSQL - Binary Log Status",
+            ViewMode::ProcessList => "RMSQL - Process List",
         };
         
         let path = navigation.get_current_path();
-        let header_text = format!("{} [{}]", title, path);
+        let time_zone = if navigation.local_time { "Local time" } else { "UTC" };
+        let badge_style = match navigation.connection_color {
+            Some(color) => Style::default().fg(badge_text_color(color)).bg(color),
+            None => Style::default().fg(self.theme.status_fg).bg(self.theme.status_bg),
+        };
+        let mut header_text = vec![
+            Span::styled(format!(" {} ", navigation.connection_name), badge_style.add_modifier(Modifier::BOLD)),
+            Span::raw(format!(" {} [{}] [{}]", title, path, time_zone)),
+        ];
+        if navigation.read_only {
+            header_text.push(Span::raw(" "));
+            header_text.push(Span::styled("[RO]", Style::default().fg(self.theme.warning).add_modifier(Modifier::BOLD)));
+        }
+        if navigation.in_transaction {
+            header_text.push(Span::raw(" "));
+            header_text.push(Span::styled("[TXN]", Style::default().fg(self.theme.error).add_modifier(Modifier::BOLD)));
+        }
         
-        let header = Paragraph::new(header_text)
+        let header = Paragraph::new(Line::from(header_text))
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().fg(self.theme.header))
             )
-            .style(Style::default().fg(Color::White));
+            .style(Style::default().fg(self.theme.text));
         
         f.render_widget(header, area);
     }
     
     fn draw_databases(&self, f: &mut Frame, area: Rect, navigation: &NavigationState) {
-        let items: Vec<ListItem> = navigation
-            .databases
-            .iter()
-            .map(|db| {
-                ListItem::new(Line::from(Span::styled(
-                    format!("📁 {}", db),
-                    Style::default().fg(Color::Yellow),
-                )))
+        let visible = navigation.visible_list_items(ViewMode::Databases, &navigation.databases);
+        let selected = navigation.database_list_state.selected();
+        let number_width = visible.len().to_string().len();
+        let items: Vec<ListItem> = visible
+            .into_iter()
+            .enumerate()
+            .map(|(position, db)| {
+                let prefix = if navigation.favorite_databases.contains(db.as_str()) { "📁 ★ " } else { "📁 " };
+                let mut line = highlighted_line(prefix, db, &navigation.list_filter, self.theme.accent);
+                if navigation.relative_numbers {
+                    let number = format!("{} ", relative_number(position, selected, number_width));
+                    line.spans.insert(0, Span::styled(number, Style::default().fg(self.theme.muted)));
+                }
+                ListItem::new(line)
             })
             .collect();
         
@@ -100,13 +1008,13 @@ impl AppUI {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Databases (j/k to navigate, l/Enter to open)")
+                    .title(list_title("Databases (j/k to navigate, l/Enter to open, / filter)".to_string(), navigation))
             )
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(self.theme.text))
             .highlight_style(
                 Style::default()
-                    .bg(Color::Blue)
-                    .fg(Color::White)
+                    .bg(self.theme.highlight_bg)
+                    .fg(self.theme.highlight_fg)
                     .add_modifier(Modifier::BOLD)
             )
             .highlight_symbol("► ");
@@ -115,34 +1023,51 @@ impl AppUI {
     }
     
     fn draw_tables(&self, f: &mut Frame, area: Rect, navigation: &NavigationState) {
-        let items: Vec<ListItem> = navigation
-            .tables
-            .iter()
-            .map(|table| {
-                ListItem::new(Line::from(Span::styled(
-                    format!("📋 {}", table),
-                    Style::default().fg(Color::Green),
-                )))
+        let visible = navigation.visible_list_items(ViewMode::Tables, &navigation.tables);
+        let name_width = visible.iter().map(|table| table.chars().count()).max().unwrap_or(0);
+        let selected = navigation.table_list_state.selected();
+        let number_width = visible.len().to_string().len();
+        let items: Vec<ListItem> = visible
+            .into_iter()
+            .enumerate()
+            .map(|(position, table)| {
+                let prefix = if navigation.favorite_tables.contains(table.as_str()) { "📋 ★ " } else { "📋 " };
+                let mut line = highlighted_line(prefix, table, &navigation.list_filter, self.theme.success);
+                if navigation.relative_numbers {
+                    let number = format!("{} ", relative_number(position, selected, number_width));
+                    line.spans.insert(0, Span::styled(number, Style::default().fg(self.theme.muted)));
+                }
+                if navigation.show_table_stats {
+                    let (rows, size) = match navigation.table_stats.get(table.as_str()) {
+                        Some((Some(rows), data_length)) => (format!("~{} rows", rows), format_bytes(*data_length)),
+                        Some((None, _)) => ("view".to_string(), String::new()),
+                        None => ("?".to_string(), String::new()),
+                    };
+                    let padding = " ".repeat(name_width - table.chars().count());
+                    line.spans.push(Span::styled(
+                        format!("{}  {:>14}  {:>9}", padding, rows, size),
+                        Style::default().fg(self.theme.muted),
+                    ));
+                }
+                ListItem::new(line)
             })
             .collect();
         
         let database_name = navigation
-            .current_database
-            .as_ref()
-            .map(|s| s.as_str())
+            .current_database.as_deref()
             .unwrap_or("None");
         
         let list = List::new(items)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(format!("Tables in '{}' (h to go back, l/Enter to view data)", database_name))
+                    .title(list_title(format!("Tables in '{}' (h to go back, l/Enter to view data, / filter, t stats)", database_name), navigation))
             )
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(self.theme.text))
             .highlight_style(
                 Style::default()
-                    .bg(Color::Blue)
-                    .fg(Color::White)
+                    .bg(self.theme.highlight_bg)
+                    .fg(self.theme.highlight_fg)
                     .add_modifier(Modifier::BOLD)
             )
             .highlight_symbol("► ");
@@ -152,127 +1077,173 @@ impl AppUI {
     
     fn draw_table_data(&self, f: &mut Frame, area: Rect, navigation: &NavigationState) {
         if navigation.table_columns.is_empty() || navigation.table_rows.is_empty() {
-            let empty_msg = Paragraph::new("No data available or table is empty")
+            let message = if navigation.table_columns.is_empty() {
+                "No data loaded".to_string()
+            } else if navigation.table_filter.is_some() {
+                match navigation.table_unfiltered_rows {
+                    Some(total) => format!("Filter matched 0 of {} rows (f to change it, Esc in the filter input clears it)", total),
+                    None => "Filter matched 0 rows".to_string(),
+                }
+            } else if navigation.table_total_rows.is_some_and(|total| total > 0) {
+                "No rows on this page".to_string()
+            } else {
+                "Table is empty".to_string()
+            };
+            let empty_msg = Paragraph::new(message)
                 .block(Block::default().borders(Borders::ALL).title("Table Data"))
-                .style(Style::default().fg(Color::Gray));
+                .style(Style::default().fg(self.theme.muted));
             f.render_widget(empty_msg, area);
             return;
         }
         
         // Split area for columns info and table data
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(5), // Column info
-                Constraint::Min(0),    // Table data
-            ])
-            .split(area);
+        let chunks = table_data_layout(area);
         
-        // Get visible column range based on expanded mode and horizontal scroll
-        let (start_col, end_col) = if navigation.expanded_columns {
-            navigation.get_visible_columns()
+        // Get visible column range based on expanded mode and horizontal scroll;
+        // positions count only the columns not hidden with the column picker
+        let (start_col, end_col) = navigation.get_visible_columns();
+        let visible = navigation.visible_column_indices();
+        let shown_count = navigation.shown_columns().len();
+        let hidden_count = navigation.table_columns.len() - shown_count;
+        let scrolled = navigation.expanded_columns || navigation.compact_columns_overflow();
+        
+        // Draw column info - show only the visible columns when the view scrolls
+        let label = |i: usize| format!("{}{}", column_key(navigation, i).marker(), navigation.table_columns[i]);
+        let column_info = if scrolled {
+            let info = visible.iter().map(|&i| label(i)).collect::<Vec<_>>().join(" | ");
+            format!("Columns {}-{} of {}: {}", start_col + 1, end_col, shown_count, info)
         } else {
-            (0, navigation.table_columns.len())
+            navigation.shown_columns().into_iter().map(label).collect::<Vec<_>>().join(" | ")
         };
+
         
-        // Draw column info - show only visible columns in expanded mode
-        let column_info = if navigation.expanded_columns {
-            let visible_cols = &navigation.table_columns[start_col..end_col];
-            let info = visible_cols.join(" | ");
-            format!("Columns {}-{} of {}: {}", start_col + 1, end_col, navigation.table_columns.len(), info)
-        } else {
-            navigation.table_columns.join(" | ")
+        let mut columns_title = match navigation.sort_order() {
+            Some((column, desc)) => format!("Columns (sorted by {} {})", column, if desc { "DESC" } else { "ASC" }),
+            None => "Columns".to_string(),
         };
+        if hidden_count > 0 {
+            columns_title.push_str(&format!(" - {} hidden, H to pick", hidden_count));
+        }
         
         let columns_widget = Paragraph::new(column_info)
-            .block(Block::default().borders(Borders::ALL).title("Columns"))
-            .style(Style::default().fg(Color::Cyan));
+            .block(Block::default().borders(Borders::ALL).title(columns_title))
+            .style(Style::default().fg(self.theme.header));
         f.render_widget(columns_widget, chunks[0]);
         
-        // Prepare table headers - only visible columns
-        let header = navigation
-            .table_columns
-            .iter()
-            .skip(start_col)
-            .take(end_col - start_col)
-            .map(|col| {
+        // Prepare table headers - only visible columns, after the relative numbers
+        let number_width = row_number_width(navigation) as usize;
+        let selected_row = navigation.data_table_state.selected();
+        let number_header = (number_width > 0).then(|| Cell::from(""));
+        let header = number_header
+            .into_iter()
+            .chain(visible.iter().map(|&i| {
+                let col = &navigation.table_columns[i];
                 // Extract just the column name (before the type info in parentheses)
                 let name = col.split(" (").next().unwrap_or(col);
-                name.to_string()
-            })
+                // Key columns are prefixed with their marker, JSON columns get a small
+                // marker too; their values open pretty-printed with v
+                let name = format!("{}{}", column_key(navigation, i).marker(), name);
+                let name = if navigation.table_json_columns.get(i).copied().unwrap_or(false) {
+                    format!("{} {{}}", name)
+                } else {
+                    name
+                };
+                let cell = aligned_cell(name, is_numeric(&navigation.table_numeric_columns, i));
+                if navigation.focused_column == Some(i) {
+                    cell.style(Style::default().fg(self.theme.match_fg).bg(self.theme.match_bg))
+                } else {
+                    cell
+                }
+            }))
             .collect::<Vec<_>>();
         
         // Prepare table rows - only visible columns
         let rows: Vec<Row> = navigation
             .table_rows
             .iter()
-            .map(|row| {
-                Row::new(
-                    row.iter()
-                        .skip(start_col)
-                        .take(end_col - start_col)
-                        .map(|cell| {
-                            // Truncate long values based on expansion mode
-                            let max_len = if navigation.expanded_columns { 100 } else { 30 };
-                            if cell.len() > max_len {
-                                let truncated = truncate_utf8(cell, max_len.saturating_sub(3));
-                                format!("{}...", truncated)
-                            } else {
-                                cell.clone()
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                )
+            .enumerate()
+            .map(|(position, row)| {
+                let number = (number_width > 0).then(|| {
+                    Cell::from(relative_number(position, selected_row, number_width)).style(Style::default().fg(self.theme.muted))
+                });
+                let cells = visible
+                    .iter()
+                    .filter_map(|&i| row.get(i).map(|cell| (i, cell)))
+                    .map(|(i, cell)| {
+                        let Some(value) = cell else {
+                            return null_cell(&self.theme);
+                        };
+                        if let Some(cell) = blank_cell(value, navigation.blank_markers, &self.theme) {
+                            return cell;
+                        }
+                        // Truncate long values based on expansion mode
+                        let max_chars = if navigation.expanded_columns {
+                            self.cell_display_chars * 2
+                        } else {
+                            self.cell_display_chars
+                        };
+                        let text = truncate_cell(display_time(value, navigation.local_time), max_chars);
+                        let cell = aligned_cell(text, is_numeric(&navigation.table_numeric_columns, i));
+                        if navigation.matches_search(value) {
+                            cell.style(Style::default().fg(self.theme.match_fg).bg(self.theme.match_bg))
+                        } else {
+                            cell
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                Row::new(number.into_iter().chain(cells))
             })
             .collect();
         
         // Calculate column widths based on expansion mode
-        let num_visible_cols = header.len().max(1);
+        let num_visible_cols = visible.len().max(1);
         let available_width = chunks[1].width.saturating_sub(2); // Account for borders
         
-        let constraints = if navigation.expanded_columns {
-            // In expanded mode, give more space to columns (minimum 20 chars each)
-            let min_col_width = 20u16;
-            let total_min_width = min_col_width * num_visible_cols as u16;
-            
-            if total_min_width <= available_width {
-                let extra_width = available_width - total_min_width;
-                let extra_per_col = extra_width / num_visible_cols as u16;
-                vec![Constraint::Length(min_col_width + extra_per_col); num_visible_cols]
-            } else {
-                vec![Constraint::Length(min_col_width); num_visible_cols]
-            }
-        } else {
-            // In normal mode, distribute space evenly
-            let col_width = available_width / num_visible_cols as u16;
-            vec![Constraint::Length(col_width); num_visible_cols]
-        };
+        let constraints = data_column_constraints(navigation, num_visible_cols, available_width);
         
         let table_name = navigation
-            .current_table
-            .as_ref()
-            .map(|s| s.as_str())
+            .current_table.as_deref()
             .unwrap_or("Unknown");
         
+        let filter = navigation
+            .table_filter
+            .as_deref()
+            .map(|filter| format!(" [WHERE {}]", filter))
+            .unwrap_or_default();
+        
         let title = if navigation.expanded_columns {
             format!(
-                "Data from '{}' [EXPANDED {}-{}/{}] (←→ navigate, Space compress, h back)", 
+                "Data from '{}'{} [{}] [EXPANDED {}-{}/{}] (←→ navigate, </> resize, Space compress, h back)", 
+                table_name,
+                filter,
+                navigation.page_description(),
+                start_col + 1,
+                end_col,
+                shown_count
+            )
+        } else if scrolled {
+            format!(
+                "Data from '{}'{} [{}] [COLUMNS {}-{}/{}] (h/l scroll, Space expand, PgUp/PgDn page, / search, f filter)", 
                 table_name,
+                filter,
+                navigation.page_description(),
                 start_col + 1,
                 end_col,
-                navigation.table_columns.len()
+                shown_count
             )
         } else {
             format!(
-                "Data from '{}' (h to go back, Space to expand, showing first 100 rows)", 
-                table_name
+                "Data from '{}'{} [{}] (h back, Space expand, PgUp/PgDn page, +/-/[/] page size, / search, f filter)", 
+                table_name,
+                filter,
+                navigation.page_description()
             )
         };
         
         let table = Table::new(rows, constraints)
             .header(
                 Row::new(header)
-                    .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+                    .style(Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD))
                     .bottom_margin(1)
             )
             .block(
@@ -280,11 +1251,11 @@ impl AppUI {
                     .borders(Borders::ALL)
                     .title(title)
             )
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(self.theme.text))
             .row_highlight_style(
                 Style::default()
-                    .bg(Color::Blue)
-                    .fg(Color::White)
+                    .bg(self.theme.highlight_bg)
+                    .fg(self.theme.highlight_fg)
                     .add_modifier(Modifier::BOLD)
             );
         
@@ -292,101 +1263,489 @@ impl AppUI {
     }
     
     fn draw_sql_editor(&self, f: &mut Frame, area: Rect, navigation: &NavigationState) {
+        // The input keeps its preferred height and grows with a longer (wrapped) buffer
+        // up to half the view; the results take what is left, at least 3 lines
+        let text_width = area.width.saturating_sub(2).max(1) as usize;
+        let buffer_lines: usize = navigation
+            .sql_input
+            .split('\n')
+            .map(|line| line.chars().count().max(1).div_ceil(text_width))
+            .sum();
+        let auto_lines = (buffer_lines as u16).min((area.height / 2).saturating_sub(2));
+        let input_lines = navigation
+            .sql_input_lines
+            .max(auto_lines)
+            .min(area.height.saturating_sub(2 + 3 + 3).max(1));
+        
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(5), // SQL input
+                Constraint::Length(input_lines + 2), // SQL input
                 Constraint::Length(3), // History info
                 Constraint::Min(0),    // Results
             ])
             .split(area);
         
         // Draw SQL input
-        let current_db = navigation.current_database
-            .as_ref()
-            .map(|s| s.as_str())
+        let current_db = navigation.current_database.as_deref()
             .unwrap_or("none");
         
         let sql_input = Paragraph::new(navigation.sql_input.as_str())
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(format!("SQL Editor - Database: {} (Enter to execute, Esc to exit, Up/Down for history)", current_db))
+                    .title(format!(
+                        "SQL Editor - Database: {} - Limit: {} (Enter to execute, Esc to exit, Up/Down for history)",
+                        current_db,
+                        navigation.row_limit.map(|limit| limit.to_string()).unwrap_or_else(|| "none".to_string())
+                    ))
             )
-            .style(Style::default().fg(Color::White))
+            .style(Style::default().fg(self.theme.text))
             .wrap(ratatui::widgets::Wrap { trim: false });
         
         f.render_widget(sql_input, chunks[0]);
         
-        // Draw history info
+        // Draw history and queue info
         let history_info = if navigation.sql_history.is_empty() {
             "No SQL history yet".to_string()
         } else {
             format!("History: {} queries saved", navigation.sql_history.len())
         };
+        let history_info = if navigation.query_queue.is_empty() {
+            history_info
+        } else {
+            format!(
+                "{} | Queue: {} queries (Ctrl+Q to queue, F5 to run all, Ctrl+D to clear)",
+                history_info,
+                navigation.query_queue.len()
+            )
+        };
         
         let history_widget = Paragraph::new(history_info)
             .block(Block::default().borders(Borders::ALL).title("History"))
-            .style(Style::default().fg(Color::Gray));
+            .style(Style::default().fg(self.theme.muted));
         
         f.render_widget(history_widget, chunks[1]);
         
         // Draw results
-        if let Some(result) = &navigation.sql_result {
-            if result.columns.is_empty() {
-                // Non-SELECT query result
-                let result_widget = Paragraph::new(result.message.as_str())
-                    .block(Block::default().borders(Borders::ALL).title("Result"))
-                    .style(Style::default().fg(Color::Green));
-                
-                f.render_widget(result_widget, chunks[2]);
-            } else {
-                // SELECT query result
-                let rows: Vec<Row> = result.rows
-                    .iter()
-                    .map(|row| {
-                        Row::new(
-                            row.iter()
-                                .map(|cell| {
-                                    if cell.len() > 50 {
-                                        let truncated = truncate_utf8(cell, 47);
-                                        format!("{}...", truncated)
-                                    } else {
-                                        cell.clone()
-                                    }
-                                })
-                                .collect::<Vec<_>>()
-                        )
-                    })
-                    .collect();
-                
-                let num_cols = result.columns.len().max(1);
-                let available_width = chunks[2].width.saturating_sub(2);
-                let col_width = available_width / num_cols as u16;
-                let constraints = vec![Constraint::Length(col_width); num_cols];
-                
-                let table = Table::new(rows, constraints)
-                    .header(
-                        Row::new(result.columns.clone())
-                            .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
-                            .bottom_margin(1)
-                    )
-                    .block(
-                        Block::default()
-                            .borders(Borders::ALL)
-                            .title(format!("Result - {}", result.message))
-                    )
-                    .style(Style::default().fg(Color::White));
-                
-                f.render_widget(table, chunks[2]);
-            }
+        if let Some(tab) = navigation.get_active_result_tab() {
+            let result_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(3), // Result tabs
+                    Constraint::Min(0),    // Active result
+                ])
+                .split(chunks[2]);
+            
+            let titles: Vec<Line> = navigation
+                .result_tabs
+                .iter()
+                .enumerate()
+                .map(|(i, tab)| {
+                    let color = if tab.result.message.starts_with("Error") {
+                        self.theme.error
+                    } else {
+                        self.theme.success
+                    };
+                    Line::from(Span::styled(format!("{}: {}", i + 1, truncate_utf8(&tab.sql, 20)), Style::default().fg(color)))
+                })
+                .collect();
+            
+            let title = format!(
+                "Result {}/{} (Tab/Shift+Tab to switch)",
+                navigation.active_result_tab + 1,
+                navigation.result_tabs.len()
+            );
+            let tabs = Tabs::new(titles)
+                .block(Block::default().borders(Borders::ALL).title(title))
+                .select(navigation.active_result_tab)
+                .highlight_style(Style::default().fg(self.theme.highlight_fg).bg(self.theme.highlight_bg).add_modifier(Modifier::BOLD));
+            
+            f.render_widget(tabs, result_chunks[0]);
+            self.draw_sql_result(f, result_chunks[1], &tab.result, navigation.local_time, navigation.blank_markers, navigation.sql_error_scroll);
+        } else if let Some(comparison) = &navigation.explain_comparison {
+            self.draw_explain_comparison(f, chunks[2], comparison, navigation.blank_markers);
+        } else if let Some(result) = &navigation.sql_result {
+            self.draw_sql_result(f, chunks[2], result, navigation.local_time, navigation.blank_markers, navigation.sql_error_scroll);
         } else {
             let placeholder = Paragraph::new("Enter SQL query above and press Enter to execute")
                 .block(Block::default().borders(Borders::ALL).title("Results"))
-                .style(Style::default().fg(Color::Gray));
+                .style(Style::default().fg(self.theme.muted));
             
             f.render_widget(placeholder, chunks[2]);
         }
+        
+        // Completion candidates drop down from the input box, over the results
+        if let Some(completion) = &navigation.completion {
+            let height = (completion.candidates.len() as u16 + 2).min(10);
+            let popup = Rect {
+                x: chunks[0].x + 1,
+                y: chunks[0].bottom(),
+                width: 40.min(area.width.saturating_sub(1)),
+                height: height.min(area.bottom().saturating_sub(chunks[0].bottom())),
+            };
+            let items: Vec<ListItem> = completion
+                .candidates
+                .iter()
+                .map(|candidate| ListItem::new(candidate.as_str()))
+                .collect();
+            let list = List::new(items)
+                .block(Block::default().borders(Borders::ALL).title("Completions"))
+                .highlight_style(Style::default().fg(self.theme.match_fg).bg(self.theme.match_bg));
+            let mut state = ListState::default();
+            state.select(Some(completion.selected));
+            f.render_widget(Clear, popup);
+            f.render_stateful_widget(list, popup, &mut state);
+        }
+    }
+    
+    fn draw_explain_comparison(&self, f: &mut Frame, area: Rect, comparison: &ExplainComparison, blank_markers: bool) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(6), // Estimate vs actual
+                Constraint::Min(0),    // Full plan
+            ])
+            .split(area);
+        
+        // Estimates off by more than 2x are suspicious, by 10x almost certainly stale
+        let (ratio_text, ratio_style, hint) = match comparison.ratio() {
+            Some(ratio) if !(0.1..=10.0).contains(&ratio) => (
+                format!("{:.2}x", ratio),
+                Style::default().fg(self.theme.error).add_modifier(Modifier::BOLD),
+                format!("Statistics look stale - consider ANALYZE TABLE {}", quote_identifier(&comparison.primary_table)),
+            ),
+            Some(ratio) if !(0.5..=2.0).contains(&ratio) => (
+                format!("{:.2}x", ratio),
+                Style::default().fg(self.theme.warning),
+                "Estimate is somewhat off".to_string(),
+            ),
+            Some(ratio) => (
+                format!("{:.2}x", ratio),
+                Style::default().fg(self.theme.success),
+                "Estimate is close to reality".to_string(),
+            ),
+            None => (
+                "n/a".to_string(),
+                Style::default().fg(self.theme.muted),
+                "No actual rows to compare against".to_string(),
+            ),
+        };
+        
+        let label_style = Style::default().fg(self.theme.accent);
+        let lines = vec![
+            Line::from(vec![
+                Span::styled("Primary table: ", label_style),
+                Span::raw(comparison.primary_table.clone()),
+            ]),
+            Line::from(vec![
+                Span::styled("Estimated rows: ", label_style),
+                Span::raw(format!("{:.0}", comparison.estimated_rows)),
+                Span::styled("   Actual rows: ", label_style),
+                Span::raw(comparison.actual_rows.to_string()),
+                Span::styled("   Ratio: ", label_style),
+                Span::styled(ratio_text, ratio_style),
+            ]),
+            Line::from(Span::styled(hint, ratio_style)),
+        ];
+        
+        let summary = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("EXPLAIN estimate vs actual"));
+        f.render_widget(summary, chunks[0]);
+        
+        let plan = SqlResult {
+            columns: comparison.plan_columns.clone(),
+            numeric_columns: comparison.plan_numeric_columns.clone(),
+            rows: comparison.plan_rows.clone(),
+            message: "Execution plan".to_string(),
+        };
+        self.draw_sql_result(f, chunks[1], &plan, false, blank_markers, 0);
+    }
+    
+    fn draw_sql_result(&self, f: &mut Frame, area: Rect, result: &SqlResult, local_time: bool, blank_markers: bool, error_scroll: u16) {
+        if let Some(error) = result.message.strip_prefix("Error: ") {
+            self.draw_sql_error(f, area, error, error_scroll);
+        } else if result.columns.is_empty() {
+            // Non-SELECT query result; the lines after the summary are SHOW WARNINGS rows
+            let mut lines = result.message.lines();
+            let mut text = vec![Line::from(lines.next().unwrap_or_default().to_string())];
+            text.extend(lines.map(|line| Line::styled(line.to_string(), Style::default().fg(self.theme.warning))));
+            let result_widget = Paragraph::new(text)
+                .block(Block::default().borders(Borders::ALL).title("Result"))
+                .style(Style::default().fg(self.theme.success))
+                .wrap(ratatui::widgets::Wrap { trim: false });
+            
+            f.render_widget(result_widget, area);
+        } else {
+            // SELECT query result
+            let rows: Vec<Row> = result.rows
+                .iter()
+                .map(|row| {
+                    Row::new(
+                        row.iter()
+                            .enumerate()
+                            .map(|(i, cell)| {
+                                let Some(cell) = cell else {
+                                    return null_cell(&self.theme);
+                                };
+                                if let Some(marker) = blank_cell(cell, blank_markers, &self.theme) {
+                                    return marker;
+                                }
+                                let text = truncate_cell(display_time(cell, local_time), self.cell_display_chars);
+                                aligned_cell(text, is_numeric(&result.numeric_columns, i))
+                            })
+                            .collect::<Vec<_>>()
+                    )
+                })
+                .collect();
+            
+            let num_cols = result.columns.len().max(1);
+            let available_width = area.width.saturating_sub(2);
+            let col_width = available_width / num_cols as u16;
+            let constraints = vec![Constraint::Length(col_width); num_cols];
+            
+            let table = Table::new(rows, constraints)
+                .header(
+                    Row::new(
+                        result.columns
+                            .iter()
+                            .enumerate()
+                            .map(|(i, col)| aligned_cell(col.clone(), is_numeric(&result.numeric_columns, i)))
+                            .collect::<Vec<_>>()
+                    )
+                        .style(Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD))
+                        .bottom_margin(1)
+                )
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(format!("Result - {}", result.message))
+                )
+                .style(Style::default().fg(self.theme.text));
+            
+            f.render_widget(table, area);
+        }
+    }
+    
+    // A failed statement: the error code and SQLSTATE on top, where truncation
+    // can't hide them, then the message wrapped and scrollable
+    fn draw_sql_error(&self, f: &mut Frame, area: Rect, error: &str, scroll: u16) {
+        let error_style = Style::default().fg(self.theme.error);
+        let (header, message) = match database::split_mysql_error(error) {
+            Some((code, state, message)) => (format!("MySQL error {} - SQLSTATE {}", code, state), message),
+            None => ("Error".to_string(), error),
+        };
+        let mut text = vec![
+            Line::styled(header, error_style.add_modifier(Modifier::BOLD)),
+            Line::from(""),
+        ];
+        text.extend(message.lines().map(|line| Line::styled(line.to_string(), Style::default().fg(self.theme.text))));
+        
+        // Stop scrolling once the last line is at the top
+        let width = area.width.saturating_sub(2).max(1) as usize;
+        let wrapped_lines: usize = message.lines().map(|line| line.chars().count().div_ceil(width).max(1)).sum();
+        let scroll = scroll.min((wrapped_lines + 1) as u16);
+        
+        let widget = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(error_style)
+                    .title("Error (PgUp/PgDn scroll)"),
+            )
+            .wrap(ratatui::widgets::Wrap { trim: false })
+            .scroll((scroll, 0));
+        f.render_widget(widget, area);
+    }
+    
+    fn draw_binlog(&self, f: &mut Frame, area: Rect, navigation: &NavigationState) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Binary Log Status (y to copy, r to refresh, h/Esc to go back)");
+        
+        let status = match &navigation.binlog_status {
+            Some(status) => status,
+            None => {
+                let message = Paragraph::new("Binary logging is disabled on this server")
+                    .block(block)
+                    .style(Style::default().fg(self.theme.muted));
+                f.render_widget(message, area);
+                return;
+            }
+        };
+        
+        let label_style = Style::default().fg(self.theme.accent);
+        let or_none = |value: &str| if value.is_empty() { "(none)".to_string() } else { value.to_string() };
+        
+        let lines = vec![
+            Line::from(vec![Span::styled("File: ", label_style), Span::raw(status.file.clone())]),
+            Line::from(vec![Span::styled("Position: ", label_style), Span::raw(status.position.to_string())]),
+            Line::from(vec![Span::styled("GTID set: ", label_style), Span::raw(or_none(&status.gtid_set))]),
+            Line::from(""),
+            Line::from(vec![Span::styled("Binlog_Do_DB: ", label_style), Span::raw(or_none(&status.do_db))]),
+            Line::from(vec![Span::styled("Binlog_Ignore_DB: ", label_style), Span::raw(or_none(&status.ignore_db))]),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("Command: {}", status.command),
+                Style::default().fg(self.theme.muted),
+            )),
+        ];
+        
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(ratatui::widgets::Wrap { trim: true });
+        
+        f.render_widget(paragraph, area);
+    }
+    
+    fn draw_process_list(&self, f: &mut Frame, area: Rect, navigation: &NavigationState) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Process List (K to kill, r to refresh, h/Esc to go back)");
+        
+        let header_style = Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD);
+        let header = Row::new(
+            ["Id", "User", "Host", "Db", "Command", "Time", "State", "Info"]
+                .into_iter()
+                .map(|title| Cell::from(title).style(header_style)),
+        );
+        let or_empty = |value: &Option<String>| value.clone().unwrap_or_default();
+        let rows: Vec<Row> = navigation
+            .process_list
+            .iter()
+            .map(|process| {
+                // Statements span lines; the grid shows them on one
+                let info = process.info.as_deref().unwrap_or_default().split_whitespace().collect::<Vec<_>>().join(" ");
+                let row = Row::new(vec![
+                    aligned_cell(process.id.to_string(), true),
+                    Cell::from(process.user.clone()),
+                    Cell::from(process.host.clone()),
+                    Cell::from(or_empty(&process.db)),
+                    Cell::from(process.command.clone()),
+                    aligned_cell(process.time.to_string(), true),
+                    Cell::from(or_empty(&process.state)),
+                    Cell::from(info),
+                ]);
+                // Idle connections are dimmed so running statements stand out
+                if process.info.is_none() {
+                    row.style(Style::default().fg(self.theme.muted))
+                } else {
+                    row
+                }
+            })
+            .collect();
+        
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(8),
+                Constraint::Length(14),
+                Constraint::Length(20),
+                Constraint::Length(14),
+                Constraint::Length(10),
+                Constraint::Length(7),
+                Constraint::Length(20),
+                Constraint::Min(20),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .row_highlight_style(Style::default().fg(self.theme.highlight_fg).bg(self.theme.highlight_bg));
+        
+        let mut state = navigation.process_list_state.clone();
+        f.render_stateful_widget(table, area, &mut state);
+    }
+    
+    fn draw_replication(&self, f: &mut Frame, area: Rect, navigation: &NavigationState) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Replication Status (r to refresh, h/Esc to go back)");
+        
+        let status = match &navigation.replication_status {
+            Some(status) => status,
+            None => {
+                let message = Paragraph::new("This server is not configured as a replica")
+                    .block(block)
+                    .style(Style::default().fg(self.theme.muted));
+                f.render_widget(message, area);
+                return;
+            }
+        };
+        
+        let running_style = |value: &str| {
+            if value == "Yes" {
+                Style::default().fg(self.theme.success)
+            } else {
+                Style::default().fg(self.theme.error).add_modifier(Modifier::BOLD)
+            }
+        };
+        let label_style = Style::default().fg(self.theme.accent);
+        
+        let (lag_text, lag_style) = match status.seconds_behind {
+            Some(0) => ("0s".to_string(), Style::default().fg(self.theme.success)),
+            Some(secs) if secs < 60 => (format!("{}s", secs), Style::default().fg(self.theme.warning)),
+            Some(secs) => (format!("{}s", secs), Style::default().fg(self.theme.error)),
+            None => ("NULL (not replicating)".to_string(), Style::default().fg(self.theme.error)),
+        };
+        
+        let source = match status.source_port {
+            Some(port) => format!("{}:{}", status.source_host, port),
+            None => status.source_host.clone(),
+        };
+        
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Overall: ", label_style),
+                if status.is_healthy() {
+                    Span::styled("HEALTHY", Style::default().fg(self.theme.success).add_modifier(Modifier::BOLD))
+                } else {
+                    Span::styled("BROKEN", Style::default().fg(self.theme.error).add_modifier(Modifier::BOLD))
+                },
+            ]),
+            Line::from(""),
+            Line::from(vec![Span::styled("Source: ", label_style), Span::raw(source)]),
+            Line::from(vec![
+                Span::styled("Source log: ", label_style),
+                Span::raw(format!(
+                    "{} @ {}",
+                    status.source_log_file,
+                    status.read_source_log_pos.map(|pos| pos.to_string()).unwrap_or_default()
+                )),
+            ]),
+            Line::from(vec![
+                Span::styled("IO thread running: ", label_style),
+                Span::styled(status.io_running.clone(), running_style(&status.io_running)),
+            ]),
+            Line::from(vec![
+                Span::styled("SQL thread running: ", label_style),
+                Span::styled(status.sql_running.clone(), running_style(&status.sql_running)),
+            ]),
+            Line::from(vec![
+                Span::styled("Seconds behind source: ", label_style),
+                Span::styled(lag_text, lag_style),
+            ]),
+        ];
+        
+        for (label, error) in [("Last IO error: ", &status.last_io_error), ("Last SQL error: ", &status.last_sql_error)] {
+            let value = if error.is_empty() {
+                Span::styled("none", Style::default().fg(self.theme.success))
+            } else {
+                Span::styled(error.clone(), Style::default().fg(self.theme.error))
+            };
+            lines.push(Line::from(vec![Span::styled(label, label_style), value]));
+        }
+        
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("Command: {}", status.command),
+            Style::default().fg(self.theme.muted),
+        )));
+        
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(ratatui::widgets::Wrap { trim: true });
+        
+        f.render_widget(paragraph, area);
     }
     
     fn draw_status_bar(
@@ -401,14 +1760,31 @@ impl AppUI {
             ViewMode::Tables => "[2] Tables", 
             ViewMode::TableData => "[3] Data",
             ViewMode::SqlEditor => "[i] SQL Editor",
+            ViewMode::Replication => "[R] Replication",
+            ViewMode::Binlog => "[B] Binlog",
+            ViewMode::ProcessList => "[P] Processes",
         };
         
-        let help_text = "Press '?' for help | q: quit | r: refresh | 1/2/3: switch modes | i: SQL editor | Space: expand columns";
-        let status_text = format!("{} | {} | {}", mode_text, status_message, help_text);
+        let mut mode_text = match navigation.auto_refresh_secs {
+            Some(secs) => format!("{} [auto-refresh: {}s]", mode_text, secs),
+            None => mode_text.to_string(),
+        };
+        // Length of the selected cell, to spot truncation and multibyte content
+        if navigation.mode == ViewMode::TableData {
+            if let Some(length) = navigation.selected_cell_length() {
+                mode_text = format!("{} | {}", mode_text, length);
+            }
+        }
+        
+        let help_text = "Press '?' for help | q: quit | r: refresh | g1/g2/g3: switch modes | i: SQL editor | Space: expand columns";
+        let status_text = match &navigation.input_prompt {
+            Some(prompt) => format!("{} | {}: {}_ | Enter to confirm, Esc to cancel", mode_text, prompt.label(), prompt.input),
+            None => format!("{} | {} | {}", mode_text, status_message, help_text),
+        };
         
         let status = Paragraph::new(status_text)
             .block(Block::default().borders(Borders::ALL))
-            .style(Style::default().fg(Color::White).bg(Color::DarkGray));
+            .style(Style::default().fg(self.theme.status_fg).bg(self.theme.status_bg));
         
         f.render_widget(status, area);
     }
@@ -1,8 +1,20 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::keymap::KeymapProfile;
+use crate::navigation::{ViewMode, DEFAULT_SQL_INPUT_LINES};
+use crate::storage;
+use crate::ui::DEFAULT_CELL_DISPLAY_CHARS;
+
+// Oldest table view settings are dropped beyond this, so the config stays small
+const MAX_TABLE_VIEWS: usize = 500;
+
+// Frequent bookkeeping (history, access times, last database) is written at most this often
+const FLUSH_DELAY: Duration = Duration::from_secs(2);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseInfo {
@@ -10,6 +22,9 @@ pub struct DatabaseInfo {
     pub connection_id: String,
     pub last_accessed: Option<chrono::DateTime<chrono::Utc>>,
     pub favorite: bool,
+    // Tables of this database pinned to the top of the Tables list
+    #[serde(default)]
+    pub favorite_tables: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,21 +38,146 @@ pub struct SqlHistoryEntry {
     pub error_message: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistoryFormat {
+    Sql,
+    Json,
+}
+
+// Which history entries to export; fields left empty match every entry.
+// Dates are local calendar days and both ends are inclusive.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistoryFilter {
+    pub connection_id: Option<String>,
+    pub database: Option<String>,
+    pub since: Option<chrono::NaiveDate>,
+    pub until: Option<chrono::NaiveDate>,
+}
+
+impl HistoryFilter {
+    fn matches(&self, entry: &SqlHistoryEntry) -> bool {
+        let day = entry.timestamp.with_timezone(&chrono::Local).date_naive();
+        self.connection_id.as_ref().is_none_or(|id| *id == entry.connection_id)
+            && self.database.as_ref().is_none_or(|db| entry.database.as_ref() == Some(db))
+            && self.since.is_none_or(|since| day >= since)
+            && self.until.is_none_or(|until| day <= until)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct UserConfig {
     pub databases: HashMap<String, DatabaseInfo>,
     pub last_selected_database: Option<String>,
     pub last_connection_id: Option<String>,
+    #[serde(default)]
+    pub last_selected_table: Option<String>,
+    #[serde(default)]
+    pub last_view_mode: Option<ViewMode>,
     pub preferences: UserPreferences,
+    #[serde(default)]
+    pub keymap_profiles: HashMap<String, KeymapProfile>,
+    // Display settings per table, keyed by connection_id:database:table
+    #[serde(default)]
+    pub table_views: HashMap<String, TableViewSettings>,
+    // Unsent SQL editor input per connection id, saved on quit
+    #[serde(default)]
+    pub sql_drafts: HashMap<String, String>,
+    // Saved queries by name; {{db}} and {{table}} are filled in when loaded
+    #[serde(default)]
+    pub snippets: HashMap<String, String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableViewSettings {
+    pub expanded_columns: bool,
+    pub visible_columns: usize,
+    // Manual column widths from </>, by column name
+    #[serde(default)]
+    pub column_widths: HashMap<String, u16>,
+    // Columns hidden with the column picker, by column name
+    #[serde(default)]
+    pub hidden_columns: HashSet<String>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserPreferences {
     pub auto_save_history: bool,
     pub max_history_entries: usize,
     pub show_execution_time: bool,
+    // Kept for existing config files but not read: UPDATE/DELETE without WHERE are
+    // always confirmed, so it isn't offered in the preferences editor
     pub confirm_dangerous_queries: bool,
     pub default_limit: Option<usize>,
+    #[serde(default = "default_true")]
+    pub show_affected_rows_preview: bool,
+    #[serde(default)]
+    pub idle_lock_minutes: Option<u64>,
+    #[serde(default)]
+    pub show_local_time: bool,
+    // 0 disables the timeout
+    #[serde(default = "default_query_timeout")]
+    pub query_timeout_secs: u64,
+    // Built-in theme name, or a themes/<name>.json file in the config dir
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    // Keep EXPLAIN runs from the editor in the SQL history
+    #[serde(default)]
+    pub save_explain_history: bool,
+    // Skip the rest of a multi-statement script after a failing statement
+    #[serde(default = "default_true")]
+    pub stop_script_on_error: bool,
+    // ORDER BY the row key when loading table data, for stable pages
+    #[serde(default = "default_true")]
+    pub order_by_primary_key: bool,
+    // Rows kept in memory per editor result, even without a row limit; 0 keeps all
+    #[serde(default = "default_max_result_rows")]
+    pub max_result_rows: usize,
+    // Text lines of the SQL editor input box, changed with Alt+Up/Alt+Down
+    #[serde(default = "default_sql_input_lines")]
+    pub sql_input_lines: u16,
+    // Show (empty) and dotted whitespace instead of blank cells
+    #[serde(default = "default_true")]
+    pub show_blank_markers: bool,
+    // Interval of auto-refresh (A, or F9 in the editor)
+    #[serde(default = "default_auto_refresh_secs")]
+    pub auto_refresh_secs: u64,
+    // Characters of a cell shown in the data grid and SQL results before "...";
+    // expanded columns show twice as many. Changed with ( and ) in the data view
+    #[serde(default = "default_cell_display_chars")]
+    pub cell_display_chars: usize,
+    // Number list and data rows by their distance from the selected one, like vim's
+    // relativenumber, to read off counts for 5j/5k
+    #[serde(default)]
+    pub relative_numbers: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_query_timeout() -> u64 {
+    30
+}
+
+fn default_max_result_rows() -> usize {
+    100_000
+}
+
+fn default_auto_refresh_secs() -> u64 {
+    5
+}
+
+fn default_cell_display_chars() -> usize {
+    DEFAULT_CELL_DISPLAY_CHARS
+}
+
+fn default_sql_input_lines() -> u16 {
+    DEFAULT_SQL_INPUT_LINES
+}
+
+fn default_theme() -> String {
+    "dark".to_string()
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -51,6 +191,12 @@ pub struct UserConfigManager {
     history: SqlHistory,
     config_path: PathBuf,
     history_path: PathBuf,
+    // Changes not yet on disk, and when the oldest of them was made
+    config_dirty: bool,
+    history_dirty: bool,
+    dirty_since: Option<Instant>,
+    // Set when a corrupt file was moved aside at startup
+    load_warning: Option<String>,
 }
 
 impl Default for UserPreferences {
@@ -61,17 +207,20 @@ impl Default for UserPreferences {
             show_execution_time: true,
             confirm_dangerous_queries: true,
             default_limit: Some(100),
-        }
-    }
-}
-
-impl Default for UserConfig {
-    fn default() -> Self {
-        Self {
-            databases: HashMap::new(),
-            last_selected_database: None,
-            last_connection_id: None,
-            preferences: UserPreferences::default(),
+            show_affected_rows_preview: true,
+            idle_lock_minutes: None,
+            show_local_time: false,
+            query_timeout_secs: default_query_timeout(),
+            theme: default_theme(),
+            save_explain_history: false,
+            stop_script_on_error: true,
+            order_by_primary_key: true,
+            max_result_rows: default_max_result_rows(),
+            sql_input_lines: default_sql_input_lines(),
+            show_blank_markers: true,
+            auto_refresh_secs: default_auto_refresh_secs(),
+            cell_display_chars: default_cell_display_chars(),
+            relative_numbers: false,
         }
     }
 }
@@ -90,14 +239,19 @@ impl UserConfigManager {
         let config_path = Self::get_config_path()?;
         let history_path = Self::get_history_path()?;
         
-        let config = Self::load_config(&config_path)?;
-        let history = Self::load_history(&history_path)?;
+        let (config, config_warning) = Self::load_config(&config_path)?;
+        let (history, history_warning) = Self::load_history(&history_path)?;
+        let warnings: Vec<String> = config_warning.into_iter().chain(history_warning).collect();
         
         Ok(Self {
             config,
             history,
             config_path,
             history_path,
+            config_dirty: false,
+            history_dirty: false,
+            dirty_since: None,
+            load_warning: (!warnings.is_empty()).then(|| warnings.join("; ")),
         })
     }
 
@@ -111,22 +265,32 @@ impl UserConfigManager {
         &mut self.config
     }
 
+    pub fn take_load_warning(&mut self) -> Option<String> {
+        self.load_warning.take()
+    }
+
     #[allow(dead_code)]
     pub fn get_history(&self) -> &SqlHistory {
         &self.history
     }
 
-    pub fn add_database(&mut self, connection_id: String, database_name: String) -> Result<()> {
-        let db_key = format!("{}:{}", connection_id, database_name);
-        let db_info = DatabaseInfo {
-            name: database_name,
-            connection_id,
-            last_accessed: Some(chrono::Utc::now()),
-            favorite: false,
-        };
-        
-        self.config.databases.insert(db_key, db_info);
-        self.save_config()
+    // Record every database a refresh found, saving the config once for the batch
+    pub fn add_databases(&mut self, connection_id: &str, names: &[String]) -> Result<()> {
+        let now = chrono::Utc::now();
+        for database_name in names {
+            let db_key = format!("{}:{}", connection_id, database_name);
+            // Keep the favorite flags of databases seen before
+            let db_info = self.config.databases.entry(db_key).or_insert_with(|| DatabaseInfo {
+                name: database_name.clone(),
+                connection_id: connection_id.to_string(),
+                last_accessed: None,
+                favorite: false,
+                favorite_tables: Vec::new(),
+            });
+            db_info.last_accessed = Some(now);
+        }
+        self.mark_config_dirty();
+        Ok(())
     }
 
     #[allow(dead_code)]
@@ -139,7 +303,6 @@ impl UserConfigManager {
         Ok(removed)
     }
 
-    #[allow(dead_code)]
     pub fn set_database_favorite(&mut self, connection_id: &str, database_name: &str, favorite: bool) -> Result<()> {
         let db_key = format!("{}:{}", connection_id, database_name);
         if let Some(db_info) = self.config.databases.get_mut(&db_key) {
@@ -153,7 +316,7 @@ impl UserConfigManager {
         let db_key = format!("{}:{}", connection_id, database_name);
         if let Some(db_info) = self.config.databases.get_mut(&db_key) {
             db_info.last_accessed = Some(chrono::Utc::now());
-            self.save_config()?;
+            self.mark_config_dirty();
         }
         Ok(())
     }
@@ -175,14 +338,38 @@ impl UserConfigManager {
         databases.into_iter().take(limit).collect()
     }
 
-    #[allow(dead_code)]
-    pub fn get_favorite_databases(&self) -> Vec<&DatabaseInfo> {
+    pub fn get_favorite_databases(&self, connection_id: &str) -> Vec<&DatabaseInfo> {
         self.config.databases
             .values()
-            .filter(|db| db.favorite)
+            .filter(|db| db.favorite && db.connection_id == connection_id)
             .collect()
     }
 
+    pub fn set_table_favorite(&mut self, connection_id: &str, database_name: &str, table: &str, favorite: bool) -> Result<()> {
+        let db_key = format!("{}:{}", connection_id, database_name);
+        // The database may not have been listed yet, e.g. when started with --database
+        let db_info = self.config.databases.entry(db_key).or_insert_with(|| DatabaseInfo {
+            name: database_name.to_string(),
+            connection_id: connection_id.to_string(),
+            last_accessed: None,
+            favorite: false,
+            favorite_tables: Vec::new(),
+        });
+        db_info.favorite_tables.retain(|name| name != table);
+        if favorite {
+            db_info.favorite_tables.push(table.to_string());
+        }
+        self.save_config()
+    }
+
+    pub fn get_favorite_tables(&self, connection_id: &str, database_name: &str) -> Vec<String> {
+        let db_key = format!("{}:{}", connection_id, database_name);
+        self.config.databases
+            .get(&db_key)
+            .map(|db| db.favorite_tables.clone())
+            .unwrap_or_default()
+    }
+
     pub fn add_sql_history(&mut self, entry: SqlHistoryEntry) -> Result<()> {
         if !self.config.preferences.auto_save_history {
             return Ok(());
@@ -196,7 +383,9 @@ impl UserConfigManager {
             self.history.entries.drain(0..excess);
         }
         
-        self.save_history()
+        self.history_dirty = true;
+        self.dirty_since.get_or_insert_with(Instant::now);
+        Ok(())
     }
 
     #[allow(dead_code)]
@@ -232,6 +421,56 @@ impl UserConfigManager {
             .collect()
     }
 
+    // Write the matching history entries, oldest first, as a .sql script with the
+    // details of each statement in comments or as JSON. Returns how many were written.
+    pub fn export_history(&self, path: &Path, format: HistoryFormat, filter: &HistoryFilter) -> Result<usize> {
+        let entries: Vec<&SqlHistoryEntry> = self.history.entries
+            .iter()
+            .filter(|entry| filter.matches(entry))
+            .collect();
+        
+        let content = match format {
+            HistoryFormat::Json => serde_json::to_string_pretty(&entries)
+                .context("Failed to serialize SQL history")?,
+            HistoryFormat::Sql => {
+                let mut script = format!(
+                    "-- rmsql SQL history, {} statements, exported {}\n",
+                    entries.len(),
+                    chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+                );
+                for entry in &entries {
+                    script.push_str(&format!(
+                        "\n-- {} | connection: {} | database: {}\n",
+                        entry.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                        entry.connection_id,
+                        entry.database.as_deref().unwrap_or("(none)")
+                    ));
+                    let time = entry.execution_time_ms.map(|ms| format!("{} ms", ms)).unwrap_or_else(|| "unknown".to_string());
+                    match (entry.success, &entry.error_message) {
+                        (true, _) => script.push_str(&format!("-- succeeded in {}\n", time)),
+                        (false, Some(error)) => script.push_str(&format!(
+                            "-- failed after {}: {}\n",
+                            time,
+                            error.lines().next().unwrap_or_default()
+                        )),
+                        (false, None) => script.push_str(&format!("-- failed after {}\n", time)),
+                    }
+                    let sql = entry.sql.trim();
+                    script.push_str(sql);
+                    if !sql.ends_with(';') {
+                        script.push(';');
+                    }
+                    script.push('\n');
+                }
+                script
+            },
+        };
+        
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(entries.len())
+    }
+
     #[allow(dead_code)]
     pub fn clear_history(&mut self) -> Result<()> {
         self.history.entries.clear();
@@ -247,10 +486,117 @@ impl UserConfigManager {
     pub fn set_last_database(&mut self, connection_id: String, database: String) -> Result<()> {
         self.config.last_connection_id = Some(connection_id);
         self.config.last_selected_database = Some(database);
+        self.mark_config_dirty();
+        Ok(())
+    }
+
+    // Remember where the user was so the next start can reopen the same view
+    pub fn set_last_session(
+        &mut self,
+        connection_id: String,
+        database: Option<String>,
+        table: Option<String>,
+        mode: ViewMode,
+    ) -> Result<()> {
+        self.config.last_connection_id = Some(connection_id);
+        if database.is_some() {
+            self.config.last_selected_database = database;
+        }
+        self.config.last_selected_table = table;
+        self.config.last_view_mode = Some(mode);
+        self.save_config()
+    }
+
+    // Snippets sorted by name, for the picker
+    pub fn list_snippets(&self) -> Vec<(String, String)> {
+        let mut snippets: Vec<(String, String)> = self.config.snippets
+            .iter()
+            .map(|(name, sql)| (name.clone(), sql.clone()))
+            .collect();
+        snippets.sort_by_key(|(name, _)| name.to_lowercase());
+        snippets
+    }
+
+    // Replaces a snippet of the same name
+    pub fn save_snippet(&mut self, name: String, sql: String) -> Result<()> {
+        self.config.snippets.insert(name, sql);
+        self.save_config()
+    }
+
+    // Whether a snippet by that name existed
+    pub fn delete_snippet(&mut self, name: &str) -> Result<bool> {
+        let removed = self.config.snippets.remove(name).is_some();
+        if removed {
+            self.save_config()?;
+        }
+        Ok(removed)
+    }
+
+    pub fn get_sql_draft(&self, connection_id: &str) -> Option<&String> {
+        self.config.sql_drafts.get(connection_id)
+    }
+
+    // An empty draft forgets the connection's entry
+    pub fn set_sql_draft(&mut self, connection_id: String, draft: &str) -> Result<()> {
+        let changed = if draft.trim().is_empty() {
+            self.config.sql_drafts.remove(&connection_id).is_some()
+        } else {
+            self.config.sql_drafts.insert(connection_id, draft.to_string()).as_deref() != Some(draft)
+        };
+        if changed {
+            self.save_config()?;
+        }
+        Ok(())
+    }
+
+    pub fn get_table_view(&self, key: &str) -> Option<&TableViewSettings> {
+        self.config.table_views.get(key)
+    }
+
+    pub fn set_table_view(&mut self, key: String, settings: TableViewSettings) -> Result<()> {
+        self.config.table_views.insert(key, settings);
+        while self.config.table_views.len() > MAX_TABLE_VIEWS {
+            let oldest = self.config.table_views
+                .iter()
+                .min_by_key(|(_, view)| view.updated_at)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(key) => self.config.table_views.remove(&key),
+                None => break,
+            };
+        }
+        self.save_config()
+    }
+
+    // Replace all preferences, e.g. from the preferences editor
+    pub fn set_preferences(&mut self, preferences: UserPreferences) -> Result<()> {
+        self.history.max_entries = preferences.max_history_entries;
+        self.config.preferences = preferences;
+        self.save_config()
+    }
+
+    pub fn set_default_limit(&mut self, limit: Option<usize>) -> Result<()> {
+        self.config.preferences.default_limit = limit;
+        self.save_config()
+    }
+
+    // Saved with the next flush, since it changes a line per key press
+    pub fn set_sql_input_lines(&mut self, lines: u16) {
+        self.config.preferences.sql_input_lines = lines;
+        self.mark_config_dirty();
+    }
+
+    // Saved with the next flush, like the input height
+    pub fn set_cell_display_chars(&mut self, chars: usize) {
+        self.config.preferences.cell_display_chars = chars;
+        self.mark_config_dirty();
+    }
+
+    pub fn set_show_local_time(&mut self, local: bool) -> Result<()> {
+        self.config.preferences.show_local_time = local;
         self.save_config()
     }
 
-    #[allow(dead_code)]
     pub fn get_last_database(&self) -> Option<(String, String)> {
         match (&self.config.last_connection_id, &self.config.last_selected_database) {
             (Some(conn_id), Some(db)) => Some((conn_id.clone(), db.clone())),
@@ -258,7 +604,31 @@ impl UserConfigManager {
         }
     }
 
-    pub fn save_config(&self) -> Result<()> {
+    fn mark_config_dirty(&mut self) {
+        self.config_dirty = true;
+        self.dirty_since.get_or_insert_with(Instant::now);
+    }
+
+    // Write pending changes once they have waited FLUSH_DELAY; called from the event loop
+    pub fn flush_if_due(&mut self) -> Result<()> {
+        match self.dirty_since {
+            Some(since) if since.elapsed() >= FLUSH_DELAY => self.flush(),
+            _ => Ok(()),
+        }
+    }
+
+    // Write whatever is still pending, e.g. before exiting
+    pub fn flush(&mut self) -> Result<()> {
+        if self.config_dirty {
+            self.save_config()?;
+        }
+        if self.history_dirty {
+            self.save_history()?;
+        }
+        Ok(())
+    }
+
+    pub fn save_config(&mut self) -> Result<()> {
         // Create config directory if it doesn't exist
         if let Some(parent) = self.config_path.parent() {
             fs::create_dir_all(parent)
@@ -268,13 +638,17 @@ impl UserConfigManager {
         let content = serde_json::to_string_pretty(&self.config)
             .context("Failed to serialize user config")?;
             
-        fs::write(&self.config_path, content)
+        storage::write_atomic(&self.config_path, &content)
             .context("Failed to write user config file")?;
             
+        self.config_dirty = false;
+        if !self.history_dirty {
+            self.dirty_since = None;
+        }
         Ok(())
     }
 
-    pub fn save_history(&self) -> Result<()> {
+    pub fn save_history(&mut self) -> Result<()> {
         // Create cache directory if it doesn't exist
         if let Some(parent) = self.history_path.parent() {
             fs::create_dir_all(parent)
@@ -284,38 +658,43 @@ impl UserConfigManager {
         let content = serde_json::to_string_pretty(&self.history)
             .context("Failed to serialize SQL history")?;
             
-        fs::write(&self.history_path, content)
+        storage::write_atomic(&self.history_path, &content)
             .context("Failed to write SQL history file")?;
             
+        self.history_dirty = false;
+        if !self.config_dirty {
+            self.dirty_since = None;
+        }
         Ok(())
     }
 
-    fn load_config(config_path: &PathBuf) -> Result<UserConfig> {
+    // A file that no longer parses is set aside and replaced by defaults, with a warning
+    fn load_config(config_path: &PathBuf) -> Result<(UserConfig, Option<String>)> {
         if !config_path.exists() {
-            return Ok(UserConfig::default());
+            return Ok((UserConfig::default(), None));
         }
 
         let content = fs::read_to_string(config_path)
             .context("Failed to read user config file")?;
         
-        let config: UserConfig = serde_json::from_str(&content)
-            .context("Failed to parse user config file")?;
-            
-        Ok(config)
+        match serde_json::from_str(&content) {
+            Ok(config) => Ok((config, None)),
+            Err(e) => Ok((UserConfig::default(), Some(storage::set_aside_corrupt(config_path, &e)))),
+        }
     }
 
-    fn load_history(history_path: &PathBuf) -> Result<SqlHistory> {
+    fn load_history(history_path: &PathBuf) -> Result<(SqlHistory, Option<String>)> {
         if !history_path.exists() {
-            return Ok(SqlHistory::default());
+            return Ok((SqlHistory::default(), None));
         }
 
         let content = fs::read_to_string(history_path)
             .context("Failed to read SQL history file")?;
         
-        let history: SqlHistory = serde_json::from_str(&content)
-            .context("Failed to parse SQL history file")?;
-            
-        Ok(history)
+        match serde_json::from_str(&content) {
+            Ok(history) => Ok((history, None)),
+            Err(e) => Ok((SqlHistory::default(), Some(storage::set_aside_corrupt(history_path, &e)))),
+        }
     }
 
     fn get_config_path() -> Result<PathBuf> {
@@ -342,6 +721,10 @@ impl Default for UserConfigManager {
                 history: SqlHistory::default(),
                 config_path,
                 history_path,
+                config_dirty: false,
+                history_dirty: false,
+                dirty_since: None,
+                load_warning: None,
             }
         })
     }